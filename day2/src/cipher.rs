@@ -0,0 +1,206 @@
+use std::cmp::Reverse;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use crate::{Move, Outcome, PartialRound, Round};
+
+// The second column of the strategy guide is encrypted: part 1 reads X/Y/Z
+// as rock/paper/scissors and part 2 reads them as lose/draw/win. Neither
+// reading is stated anywhere, so this tries every way X/Y/Z could map onto
+// distinct moves or distinct outcomes and scores the guide under each one.
+#[derive(Clone, Copy, Debug)]
+pub enum Cipher {
+    Moves([Move; 3]),
+    Outcomes([Outcome; 3]),
+}
+
+impl Cipher {
+    const SYMBOLS: [char; 3] = ['X', 'Y', 'Z'];
+
+    fn all() -> Vec<Self> {
+        let move_ciphers = permutations(Move::ALL).into_iter().map(Self::Moves);
+        let outcome_ciphers = permutations(Outcome::ALL).into_iter().map(Self::Outcomes);
+        move_ciphers.chain(outcome_ciphers).collect()
+    }
+
+    fn symbol_index(char: char) -> usize {
+        Self::SYMBOLS
+            .iter()
+            .position(|&symbol| symbol == char)
+            .expect("Could not find symbol for char")
+    }
+
+    fn round_score(&self, opponents_move: Move, symbol_index: usize) -> u32 {
+        match self {
+            Self::Moves(moves) => Round {
+                opponents_move,
+                your_move: moves[symbol_index],
+            }
+            .score(),
+            Self::Outcomes(outcomes) => PartialRound {
+                opponents_move,
+                outcome: outcomes[symbol_index],
+            }
+            .resolve()
+            .score(),
+        }
+    }
+}
+
+impl fmt::Display for Cipher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let meanings = match self {
+            Self::Moves(moves) => moves.map(|move_type| format!("{:?}", move_type)),
+            Self::Outcomes(outcomes) => outcomes.map(|outcome| format!("{:?}", outcome)),
+        };
+        let pairs = Self::SYMBOLS
+            .iter()
+            .zip(meanings)
+            .map(|(symbol, meaning)| format!("{}={}", symbol, meaning))
+            .collect::<Vec<_>>();
+        write!(f, "{}", pairs.join(" "))
+    }
+}
+
+// all six orderings of three items
+fn permutations<T: Copy>(items: [T; 3]) -> Vec<[T; 3]> {
+    let [a, b, c] = items;
+    vec![
+        [a, b, c],
+        [a, c, b],
+        [b, a, c],
+        [b, c, a],
+        [c, a, b],
+        [c, b, a],
+    ]
+}
+
+pub struct ScoredCipher {
+    cipher: Cipher,
+    total_score: u32,
+}
+
+pub struct CipherAnalyzer {
+    // `symbol_counts[m][s]` is how many rounds pair opponent move
+    // `Move::ALL[m]` with second-column symbol `Cipher::SYMBOLS[s]`; every
+    // cipher can be scored from these nine counts alone
+    symbol_counts: [[u32; 3]; 3],
+}
+
+impl CipherAnalyzer {
+    pub fn new(file_path: &str) -> Self {
+        let file = File::open(file_path).unwrap();
+        let reader = BufReader::new(file);
+
+        let mut symbol_counts = [[0; 3]; 3];
+        for line in reader.lines() {
+            let line_content = line.unwrap();
+            let strs = line_content.split(' ').collect::<Vec<_>>();
+            let opponents_move = Move::from_char(strs[0].parse::<char>().unwrap());
+            let move_index = Move::ALL
+                .iter()
+                .position(|&move_type| move_type == opponents_move)
+                .unwrap();
+            let symbol_index = Cipher::symbol_index(strs[1].parse::<char>().unwrap());
+            symbol_counts[move_index][symbol_index] += 1;
+        }
+
+        Self { symbol_counts }
+    }
+
+    fn total_score(&self, cipher: &Cipher) -> u32 {
+        let mut total_score = 0;
+        for (move_index, counts) in self.symbol_counts.iter().enumerate() {
+            for (symbol_index, count) in counts.iter().enumerate() {
+                total_score += count * cipher.round_score(Move::ALL[move_index], symbol_index);
+            }
+        }
+        total_score
+    }
+
+    // every cipher, highest total score first
+    pub fn analyze(&self) -> CipherReport {
+        let mut scored_ciphers = Cipher::all()
+            .into_iter()
+            .map(|cipher| ScoredCipher {
+                total_score: self.total_score(&cipher),
+                cipher,
+            })
+            .collect::<Vec<_>>();
+        scored_ciphers.sort_by_key(|scored| Reverse(scored.total_score));
+
+        CipherReport { scored_ciphers }
+    }
+}
+
+pub struct CipherReport {
+    scored_ciphers: Vec<ScoredCipher>,
+}
+
+impl CipherReport {
+    fn max_score(&self) -> u32 {
+        self.scored_ciphers.first().unwrap().total_score
+    }
+
+    fn min_score(&self) -> u32 {
+        self.scored_ciphers.last().unwrap().total_score
+    }
+
+    // ties are all reported, since nothing in the guide favors one over another
+    fn with_score(&self, total_score: u32) -> impl Iterator<Item = &ScoredCipher> {
+        self.scored_ciphers
+            .iter()
+            .filter(move |scored| scored.total_score == total_score)
+    }
+
+    fn mean_score(&self) -> f64 {
+        let sum: u32 = self
+            .scored_ciphers
+            .iter()
+            .map(|scored| scored.total_score)
+            .sum();
+        sum as f64 / self.scored_ciphers.len() as f64
+    }
+
+    fn std_dev(&self) -> f64 {
+        let mean = self.mean_score();
+        let variance = self
+            .scored_ciphers
+            .iter()
+            .map(|scored| (scored.total_score as f64 - mean).powi(2))
+            .sum::<f64>()
+            / self.scored_ciphers.len() as f64;
+        variance.sqrt()
+    }
+}
+
+impl fmt::Display for CipherReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for scored in self.scored_ciphers.iter() {
+            writeln!(f, "{:>8}  {}", scored.total_score, scored.cipher)?;
+        }
+        writeln!(f)?;
+
+        for scored in self.with_score(self.max_score()) {
+            writeln!(f, "Best:  {} ({})", scored.cipher, scored.total_score)?;
+        }
+        for scored in self.with_score(self.min_score()) {
+            writeln!(f, "Worst: {} ({})", scored.cipher, scored.total_score)?;
+        }
+
+        let spread = self.max_score() - self.min_score();
+        writeln!(
+            f,
+            "Spread: {} ({:.1}% of the best score)",
+            spread,
+            100.0 * spread as f64 / self.max_score() as f64
+        )?;
+        write!(
+            f,
+            "Mean: {:.1}, standard deviation: {:.1}",
+            self.mean_score(),
+            self.std_dev()
+        )
+    }
+}
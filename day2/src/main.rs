@@ -1,17 +1,74 @@
+use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
-fn main() {
-    let analyzer = TournamentAnalyzer::new("resources/input_1");
-    let total_score = analyzer.score();
-    println!("Part 1 solution: {}", total_score);
+mod cipher;
+mod strategy;
+mod timeline;
+
+use cipher::CipherAnalyzer;
+use strategy::{
+    AlwaysRock, BeatLast, CopyLast, Cycle, FollowGuide, FrequencyCounter, Strategy, Tournament,
+};
+use timeline::Timeline;
 
-    let analyzer2 = Part2Analyzer::new("resources/input_1");
-    let part_2_score = analyzer2.score();
-    println!("Part 2 solution: {}", part_2_score);
+fn main() {
+    let args = env::args().collect::<Vec<_>>();
+    match args.get(1).map(String::as_str) {
+        None => {
+            let analyzer = TournamentAnalyzer::new("resources/input_1");
+            let total_score = analyzer.score();
+            println!("Part 1 solution: {}", total_score);
+
+            let analyzer2 = Part2Analyzer::new("resources/input_1");
+            let part_2_score = analyzer2.score();
+            println!("Part 2 solution: {}", part_2_score);
+        }
+        Some("tournament") => {
+            let num_rounds = args
+                .get(2)
+                .map(|n| {
+                    n.parse::<usize>()
+                        .expect("Number of rounds must be an integer")
+                })
+                .unwrap_or(1_000);
+            let analyzer = TournamentAnalyzer::new("resources/input_1");
+            let strategies: Vec<Box<dyn Strategy>> = vec![
+                Box::new(AlwaysRock),
+                Box::new(Cycle),
+                Box::new(CopyLast),
+                Box::new(BeatLast),
+                Box::new(FrequencyCounter),
+                Box::new(FollowGuide::new(&analyzer)),
+            ];
+            let tournament = Tournament::new(strategies);
+            println!("{}", tournament.run(num_rounds));
+        }
+        Some("cipher") => {
+            let analyzer = CipherAnalyzer::new("resources/input_1");
+            println!("{}", analyzer.analyze());
+        }
+        // e.g. `timeline part2 json`; the summary goes to stderr so that
+        // stdout can be redirected straight into a .csv or .json file
+        Some("timeline") => {
+            let rounds = match args.get(2).map(String::as_str) {
+                Some("part1") => TournamentAnalyzer::new("resources/input_1").rounds,
+                Some("part2") => Part2Analyzer::new("resources/input_1").rounds(),
+                _ => panic!("Expected `part1` or `part2`"),
+            };
+            let timeline = Timeline::new(&rounds);
+            match args.get(3).map(String::as_str) {
+                Some("csv") | None => print!("{}", timeline.to_csv()),
+                Some("json") => print!("{}", timeline.to_json()),
+                Some(other) => panic!("Unknown timeline format {}", other),
+            }
+            eprintln!("{}", timeline.summary());
+        }
+        Some(other) => panic!("Unknown subcommand {}", other),
+    }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Move {
     Rock,
     Paper,
@@ -19,6 +76,8 @@ enum Move {
 }
 
 impl Move {
+    const ALL: [Move; 3] = [Self::Rock, Self::Paper, Self::Scissors];
+
     fn score(&self) -> u32 {
         match self {
             Self::Rock => 1,
@@ -38,9 +97,18 @@ impl Move {
             _ => panic!("Could not find move for char"),
         }
     }
+
+    // the move that wins against this one
+    fn beaten_by(&self) -> Self {
+        match self {
+            Self::Rock => Self::Paper,
+            Self::Paper => Self::Scissors,
+            Self::Scissors => Self::Rock,
+        }
+    }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy, Debug)]
 enum Outcome {
     Win,
     Lose,
@@ -48,6 +116,8 @@ enum Outcome {
 }
 
 impl Outcome {
+    const ALL: [Outcome; 3] = [Self::Lose, Self::Draw, Self::Win];
+
     fn score(&self) -> u32 {
         match self {
             Self::Win => 6,
@@ -66,6 +136,7 @@ impl Outcome {
     }
 }
 
+#[derive(Clone)]
 struct Round {
     opponents_move: Move,
     your_move: Move,
@@ -118,47 +189,28 @@ impl TournamentAnalyzer {
     }
 }
 
-#[derive(Clone)]
 struct PartialRound {
     opponents_move: Move,
     outcome: Outcome,
 }
 
-struct FullRound {
-    opponents_move: Move,
-    your_move: Move,
-    outcome: Outcome,
-}
-
-impl FullRound {
-    fn from_partial(partial: PartialRound) -> Self {
-        use Move::*;
+impl PartialRound {
+    // pick whichever move produces the desired outcome
+    fn resolve(&self) -> Round {
         use Outcome::*;
-        let opponents_move = partial.opponents_move;
-        let outcome = partial.outcome;
-        let your_move = match (opponents_move.clone(), outcome.clone()) {
-            (Rock, Win) => Paper,
-            (Rock, Lose) => Scissors,
-            (Scissors, Win) => Rock,
-            (Scissors, Lose) => Paper,
-            (Paper, Win) => Scissors,
-            (Paper, Lose) => Rock,
-            (move_type, Draw) => move_type,
+        let opponents_move = self.opponents_move;
+        let your_move = match self.outcome {
+            Win => opponents_move.beaten_by(),
+            Lose => opponents_move.beaten_by().beaten_by(),
+            Draw => opponents_move,
         };
-        FullRound {
-            your_move,
+        Round {
             opponents_move,
-            outcome,
+            your_move,
         }
     }
 }
 
-impl FullRound {
-    fn score(&self) -> u32 {
-        self.outcome.score() + self.your_move.score()
-    }
-}
-
 struct Part2Analyzer {
     partial_rounds: Vec<PartialRound>,
 }
@@ -181,10 +233,17 @@ impl Part2Analyzer {
         Self { partial_rounds }
     }
 
+    fn rounds(&self) -> Vec<Round> {
+        self.partial_rounds
+            .iter()
+            .map(|partial| partial.resolve())
+            .collect()
+    }
+
     fn score(&self) -> u32 {
         self.partial_rounds
             .iter()
-            .map(|partial| FullRound::from_partial(partial.clone()).score())
+            .map(|partial| partial.resolve().score())
             .sum()
     }
 }
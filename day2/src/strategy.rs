@@ -0,0 +1,230 @@
+use std::fmt;
+
+use crate::{Move, Round, TournamentAnalyzer};
+
+// A strategy sees every round played so far from its own point of view
+// (`your_move` is the move it made, `opponents_move` is what it faced) and
+// picks its next move. Strategies are stateless so that one instance can
+// safely play any number of matches, including against itself.
+pub trait Strategy {
+    fn name(&self) -> String;
+    fn choose(&self, history: &[Round]) -> Move;
+}
+
+pub struct AlwaysRock;
+
+impl Strategy for AlwaysRock {
+    fn name(&self) -> String {
+        String::from("always-rock")
+    }
+
+    fn choose(&self, _history: &[Round]) -> Move {
+        Move::Rock
+    }
+}
+
+// rock, paper, scissors, rock, paper, ...
+pub struct Cycle;
+
+impl Strategy for Cycle {
+    fn name(&self) -> String {
+        String::from("cycle")
+    }
+
+    fn choose(&self, history: &[Round]) -> Move {
+        Move::ALL[history.len() % Move::ALL.len()]
+    }
+}
+
+// plays whatever the opponent played last round; rock to open
+pub struct CopyLast;
+
+impl Strategy for CopyLast {
+    fn name(&self) -> String {
+        String::from("copy-last")
+    }
+
+    fn choose(&self, history: &[Round]) -> Move {
+        history
+            .last()
+            .map(|round| round.opponents_move)
+            .unwrap_or(Move::Rock)
+    }
+}
+
+// plays the move that would have beaten the opponent's last move; rock to open
+pub struct BeatLast;
+
+impl Strategy for BeatLast {
+    fn name(&self) -> String {
+        String::from("beat-last")
+    }
+
+    fn choose(&self, history: &[Round]) -> Move {
+        history
+            .last()
+            .map(|round| round.opponents_move.beaten_by())
+            .unwrap_or(Move::Rock)
+    }
+}
+
+// counters the opponent's most frequent move so far; ties go to whichever
+// move comes first in `Move::ALL`
+pub struct FrequencyCounter;
+
+impl Strategy for FrequencyCounter {
+    fn name(&self) -> String {
+        String::from("frequency-counter")
+    }
+
+    fn choose(&self, history: &[Round]) -> Move {
+        let mut counts = [0; 3];
+        for round in history {
+            let index = Move::ALL
+                .iter()
+                .position(|&move_type| move_type == round.opponents_move)
+                .unwrap();
+            counts[index] += 1;
+        }
+
+        let most_frequent = Move::ALL
+            .iter()
+            .zip(counts)
+            .rev() // so that `max_by_key` keeps the earliest move on ties
+            .max_by_key(|&(_, count)| count)
+            .map(|(&move_type, _)| move_type)
+            .unwrap();
+        most_frequent.beaten_by()
+    }
+}
+
+// plays the moves from the encrypted strategy guide in order, read the
+// part 1 way (X/Y/Z as moves), starting over once it runs out; an empty
+// guide always plays rock
+pub struct FollowGuide {
+    moves: Vec<Move>,
+}
+
+impl FollowGuide {
+    pub fn new(analyzer: &TournamentAnalyzer) -> Self {
+        Self {
+            moves: analyzer
+                .rounds
+                .iter()
+                .map(|round| round.your_move)
+                .collect(),
+        }
+    }
+}
+
+impl Strategy for FollowGuide {
+    fn name(&self) -> String {
+        String::from("follow-guide")
+    }
+
+    fn choose(&self, history: &[Round]) -> Move {
+        if self.moves.is_empty() {
+            return Move::Rock;
+        }
+        self.moves[history.len() % self.moves.len()]
+    }
+}
+
+pub struct Tournament {
+    strategies: Vec<Box<dyn Strategy>>,
+}
+
+impl Tournament {
+    pub fn new(strategies: Vec<Box<dyn Strategy>>) -> Self {
+        Self { strategies }
+    }
+
+    // plays every pair of distinct strategies against each other
+    pub fn run(&self, num_rounds: usize) -> ScoreMatrix {
+        let num_strategies = self.strategies.len();
+        let mut scores = vec![vec![None; num_strategies]; num_strategies];
+
+        for (i, first) in self.strategies.iter().enumerate() {
+            for (j, second) in self.strategies.iter().enumerate().skip(i + 1) {
+                let (first_score, second_score) =
+                    Self::play_match(first.as_ref(), second.as_ref(), num_rounds);
+                scores[i][j] = Some(first_score);
+                scores[j][i] = Some(second_score);
+            }
+        }
+
+        ScoreMatrix {
+            names: self
+                .strategies
+                .iter()
+                .map(|strategy| strategy.name())
+                .collect(),
+            scores,
+        }
+    }
+
+    fn play_match(first: &dyn Strategy, second: &dyn Strategy, num_rounds: usize) -> (u32, u32) {
+        let mut first_history = Vec::with_capacity(num_rounds);
+        let mut second_history = Vec::with_capacity(num_rounds);
+
+        for _ in 0..num_rounds {
+            let first_move = first.choose(&first_history);
+            let second_move = second.choose(&second_history);
+            first_history.push(Round {
+                opponents_move: second_move,
+                your_move: first_move,
+            });
+            second_history.push(Round {
+                opponents_move: first_move,
+                your_move: second_move,
+            });
+        }
+
+        let total = |history: &[Round]| history.iter().map(|round| round.score()).sum();
+        (total(&first_history), total(&second_history))
+    }
+}
+
+// `scores[i][j]` is the total score strategy `i` earned in its match against
+// strategy `j`; strategies don't play themselves, so the diagonal is empty
+pub struct ScoreMatrix {
+    names: Vec<String>,
+    scores: Vec<Vec<Option<u32>>>,
+}
+
+impl ScoreMatrix {
+    fn total(&self, index: usize) -> u32 {
+        self.scores[index].iter().flatten().sum()
+    }
+}
+
+impl fmt::Display for ScoreMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self
+            .names
+            .iter()
+            .map(|name| name.len())
+            .max()
+            .unwrap_or(0)
+            .max("total".len());
+
+        write!(f, "{:width$}", "", width = width)?;
+        for name in self.names.iter() {
+            write!(f, " {:>width$}", name, width = width)?;
+        }
+        writeln!(f, " {:>width$}", "total", width = width)?;
+
+        for (i, name) in self.names.iter().enumerate() {
+            write!(f, "{:width$}", name, width = width)?;
+            for score in self.scores[i].iter() {
+                match score {
+                    Some(score) => write!(f, " {:>width$}", score, width = width)?,
+                    None => write!(f, " {:>width$}", "-", width = width)?,
+                }
+            }
+            writeln!(f, " {:>width$}", self.total(i), width = width)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+
+use crate::{Move, Outcome, Round};
+
+struct TimelineEntry {
+    opponents_move: Move,
+    your_move: Move,
+    outcome: Outcome,
+    shape_points: u32,
+    outcome_points: u32,
+    running_total: u32,
+}
+
+// A round-by-round breakdown of how a total score was reached, for tracking
+// down which rounds contributed what.
+pub struct Timeline {
+    entries: Vec<TimelineEntry>,
+}
+
+impl Timeline {
+    const COLUMNS: [&'static str; 7] = [
+        "round",
+        "opponents_move",
+        "your_move",
+        "outcome",
+        "shape_points",
+        "outcome_points",
+        "running_total",
+    ];
+
+    pub fn new(rounds: &[Round]) -> Self {
+        let mut running_total = 0;
+        let entries = rounds
+            .iter()
+            .map(|round| {
+                let outcome = round.outcome();
+                let shape_points = round.your_move.score();
+                let outcome_points = outcome.score();
+                running_total += shape_points + outcome_points;
+                TimelineEntry {
+                    opponents_move: round.opponents_move,
+                    your_move: round.your_move,
+                    outcome,
+                    shape_points,
+                    outcome_points,
+                    running_total,
+                }
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    // rounds are numbered from one, matching the line numbers of the guide
+    fn fields(&self) -> impl Iterator<Item = [String; 7]> + '_ {
+        self.entries.iter().enumerate().map(|(i, entry)| {
+            [
+                (i + 1).to_string(),
+                format!("{:?}", entry.opponents_move),
+                format!("{:?}", entry.your_move),
+                format!("{:?}", entry.outcome),
+                entry.shape_points.to_string(),
+                entry.outcome_points.to_string(),
+                entry.running_total.to_string(),
+            ]
+        })
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = Self::COLUMNS.join(",");
+        csv.push('\n');
+        for fields in self.fields() {
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    // every field is either a number or a bare move/outcome name, so nothing
+    // needs escaping
+    pub fn to_json(&self) -> String {
+        let rounds = self
+            .fields()
+            .map(|fields| {
+                let members = Self::COLUMNS
+                    .iter()
+                    .zip(fields.iter())
+                    .enumerate()
+                    .map(|(i, (column, field))| {
+                        if (1..=3).contains(&i) {
+                            format!("\"{}\":\"{}\"", column, field)
+                        } else {
+                            format!("\"{}\":{}", column, field)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                format!("    {{{}}}", members.join(","))
+            })
+            .collect::<Vec<_>>();
+
+        let summary = self.summary();
+        format!(
+            "{{\n  \"rounds\": [\n{}\n  ],\n  \"summary\": {{\"wins\":{},\"draws\":{},\"losses\":{},\"longest_win_streak\":{},\"total\":{}}}\n}}\n",
+            rounds.join(",\n"),
+            summary.wins,
+            summary.draws,
+            summary.losses,
+            summary.longest_win_streak,
+            summary.total
+        )
+    }
+
+    pub fn summary(&self) -> Summary {
+        let mut summary = Summary {
+            wins: 0,
+            draws: 0,
+            losses: 0,
+            longest_win_streak: 0,
+            total: self
+                .entries
+                .last()
+                .map(|entry| entry.running_total)
+                .unwrap_or(0),
+        };
+
+        let mut win_streak = 0;
+        for entry in self.entries.iter() {
+            match entry.outcome {
+                Outcome::Win => {
+                    summary.wins += 1;
+                    win_streak += 1;
+                    summary.longest_win_streak = summary.longest_win_streak.max(win_streak);
+                }
+                Outcome::Draw => {
+                    summary.draws += 1;
+                    win_streak = 0;
+                }
+                Outcome::Lose => {
+                    summary.losses += 1;
+                    win_streak = 0;
+                }
+            }
+        }
+
+        summary
+    }
+}
+
+pub struct Summary {
+    wins: usize,
+    draws: usize,
+    losses: usize,
+    longest_win_streak: usize,
+    total: u32,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Wins: {}, draws: {}, losses: {}, longest win streak: {}, total score: {}",
+            self.wins, self.draws, self.losses, self.longest_win_streak, self.total
+        )
+    }
+}
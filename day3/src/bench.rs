@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use crate::{PriorityTable, RucksackAnalyzer};
+
+// Times the bitmask-based `RucksackAnalyzer` against the original
+// HashSet-based approach on a large synthetic input, and checks that both
+// produce the same answers.
+pub fn run(num_rucksacks: usize) {
+    let num_rucksacks = num_rucksacks - num_rucksacks % 3; // whole groups only
+    let lines = synthetic_lines(num_rucksacks);
+    println!("Benchmarking {} synthetic rucksacks", num_rucksacks);
+
+    let (bitmask_answers, bitmask_time) = time(|| {
+        let analyzer = RucksackAnalyzer::from_lines(
+            lines.iter().cloned(),
+            RucksackAnalyzer::DEFAULT_COMPARTMENT_COUNT,
+            RucksackAnalyzer::DEFAULT_GROUP_SIZE,
+            PriorityTable::default(),
+        )
+        .unwrap();
+        (
+            analyzer.common_priority_sum().unwrap(),
+            analyzer.group_badge_priority_sum().unwrap(),
+        )
+    });
+    let (hashset_answers, hashset_time) = time(|| hashset_priority_sums(&lines));
+
+    assert_eq!(
+        bitmask_answers, hashset_answers,
+        "Bitmask and HashSet answers differ"
+    );
+    println!("Answers: {:?}", bitmask_answers);
+    println!("HashSet: {:?}", hashset_time);
+    println!("Bitmask: {:?}", bitmask_time);
+    println!(
+        "Speedup: {:.1}x",
+        hashset_time.as_secs_f64() / bitmask_time.as_secs_f64()
+    );
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+// The approach `RucksackAnalyzer` used before switching to bitmasks, kept
+// only as a baseline to measure against.
+fn hashset_priority_sums(lines: &[String]) -> (u32, u32) {
+    let table = PriorityTable::default();
+    let to_item = |name| table.item(table.index_of(name).unwrap());
+    let rucksacks = lines
+        .iter()
+        .map(|line| {
+            let (first_half, second_half) = line.split_at(line.len() / 2);
+            let compartment_1 = first_half.chars().map(to_item).collect::<Vec<_>>();
+            let compartment_2 = second_half.chars().map(to_item).collect::<Vec<_>>();
+            (compartment_1, compartment_2)
+        })
+        .collect::<Vec<_>>();
+
+    let common_sum = rucksacks
+        .iter()
+        .map(|(compartment_1, compartment_2)| {
+            let compartment_1_set = compartment_1.iter().collect::<HashSet<_>>();
+            let compartment_2_set = compartment_2.iter().collect::<HashSet<_>>();
+            let common_items = compartment_1_set
+                .intersection(&compartment_2_set)
+                .collect::<Vec<_>>();
+            common_items[0].priority()
+        })
+        .sum();
+
+    let badge_sum = rucksacks
+        .chunks_exact(3)
+        .map(|triad| {
+            let content_sets = triad
+                .iter()
+                .map(|(compartment_1, compartment_2)| {
+                    let mut combined = compartment_1.clone();
+                    combined.extend(compartment_2.clone());
+                    combined.into_iter().collect::<HashSet<_>>()
+                })
+                .collect::<Vec<_>>();
+            let first_second = content_sets[0]
+                .intersection(&content_sets[1])
+                .cloned()
+                .collect::<HashSet<_>>();
+            let full_inter = first_second
+                .intersection(&content_sets[2])
+                .collect::<Vec<_>>();
+            full_inter[0].priority()
+        })
+        .sum();
+
+    (common_sum, badge_sum)
+}
+
+// Generates groups of three rucksacks that honor the puzzle's guarantees:
+// each rucksack's compartments share exactly one item, and each group shares
+// exactly one badge. Every group splits the 51 non-badge item types into
+// three disjoint pools, one per member, so no second badge can sneak in.
+fn synthetic_lines(num_rucksacks: usize) -> Vec<String> {
+    let table = PriorityTable::default();
+    let mut rng = XorShift(0x2022_1203);
+    let mut lines = Vec::with_capacity(num_rucksacks);
+
+    while lines.len() < num_rucksacks {
+        let mut indexes = (0..52).collect::<Vec<usize>>();
+        rng.shuffle(&mut indexes);
+        let badge = indexes[0];
+
+        for pool in indexes[1..].chunks(17) {
+            let (first_pool, second_pool) = pool.split_at(8);
+            let common = first_pool[rng.below(first_pool.len())];
+            let compartment_len = 4 + rng.below(12);
+
+            let mut compartment_1 = vec![common, badge];
+            let mut compartment_2 = vec![common];
+            while compartment_1.len() < compartment_len {
+                compartment_1.push(first_pool[rng.below(first_pool.len())]);
+            }
+            while compartment_2.len() < compartment_len {
+                compartment_2.push(second_pool[rng.below(second_pool.len())]);
+            }
+            rng.shuffle(&mut compartment_1);
+            rng.shuffle(&mut compartment_2);
+
+            lines.push(
+                compartment_1
+                    .iter()
+                    .chain(compartment_2.iter())
+                    .map(|&index| table.item(index).name)
+                    .collect(),
+            );
+        }
+    }
+
+    lines
+}
+
+// a tiny deterministic PRNG, so benchmark inputs are reproducible
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
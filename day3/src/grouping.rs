@@ -0,0 +1,294 @@
+use std::fmt;
+
+use crate::{ItemSet, RucksackAnalyzer, RucksackError};
+
+#[derive(Clone)]
+struct Group {
+    // indexes into `RucksackAnalyzer::rucksacks`
+    members: Vec<usize>,
+    // index of the badge in the `PriorityTable`
+    badge: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Uniqueness {
+    Unique,
+    NotUnique,
+    // the search hit its node limit before it could rule out a second grouping
+    Unknown,
+}
+
+pub struct Grouping<'a> {
+    analyzer: &'a RucksackAnalyzer,
+    groups: Vec<Group>,
+    uniqueness: Uniqueness,
+}
+
+impl Grouping<'_> {
+    pub fn badge_priority_sum(&self) -> u32 {
+        self.groups
+            .iter()
+            .map(|group| self.analyzer.table.item(group.badge).priority())
+            .sum()
+    }
+}
+
+impl fmt::Display for Grouping<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for group in self.groups.iter() {
+            let line_numbers = group
+                .members
+                .iter()
+                .map(|&member| self.analyzer.rucksacks[member].line_number)
+                .collect::<Vec<_>>();
+            writeln!(
+                f,
+                "Lines {:?} share {:?}",
+                line_numbers,
+                self.analyzer.table.item(group.badge).name
+            )?;
+        }
+        writeln!(f, "Uniqueness: {:?}", self.uniqueness)?;
+        write!(f, "Badge priority sum: {}", self.badge_priority_sum())
+    }
+}
+
+// Partitions rucksacks that arrive in no particular order into groups whose
+// members share exactly one item. Every candidate group is enumerated up
+// front from the item-occurrence lists, which turns this into an exact cover
+// problem: pick disjoint candidates that cover every rucksack. The search
+// always places the rucksack with the fewest candidate groups still
+// available, so dead ends show up early rather than deep in the search. It
+// keeps going after the first grouping it finds, to learn whether there's a
+// second one.
+pub struct BadgeGroupSolver<'a> {
+    analyzer: &'a RucksackAnalyzer,
+    candidates: Vec<Group>,
+    // `candidates_by_rucksack[r]` lists every candidate that includes rucksack `r`
+    candidates_by_rucksack: Vec<Vec<usize>>,
+    node_limit: usize,
+}
+
+struct SearchState {
+    placed: Vec<bool>,
+    // a candidate is live while none of its members have been placed
+    live: Vec<bool>,
+    // how many live candidates each rucksack belongs to
+    live_counts: Vec<usize>,
+    groups: Vec<usize>,
+    first_grouping: Option<Vec<usize>>,
+    groupings_found: usize,
+    nodes_visited: usize,
+}
+
+impl<'a> BadgeGroupSolver<'a> {
+    pub const DEFAULT_NODE_LIMIT: usize = 1_000_000;
+
+    pub fn new(analyzer: &'a RucksackAnalyzer, node_limit: usize) -> Self {
+        let contents = analyzer
+            .rucksacks
+            .iter()
+            .map(|rucksack| rucksack.contents())
+            .collect::<Vec<_>>();
+
+        // `occurrences[i]` lists every rucksack holding the item at table index `i`
+        let mut occurrences = vec![Vec::new(); ItemSet::CAPACITY];
+        for (rucksack_index, rucksack_contents) in contents.iter().enumerate() {
+            for item_index in rucksack_contents.indexes() {
+                occurrences[item_index].push(rucksack_index);
+            }
+        }
+
+        // each candidate is found exactly once: from its lowest-index member,
+        // with its one shared item as the badge
+        let mut candidates = Vec::new();
+        for (first, first_contents) in contents.iter().enumerate() {
+            for badge in first_contents.indexes() {
+                let partners = occurrences[badge]
+                    .iter()
+                    .cloned()
+                    .filter(|&partner| partner > first)
+                    .collect::<Vec<_>>();
+                Self::collect_candidates(
+                    analyzer.group_size,
+                    &contents,
+                    badge,
+                    &partners,
+                    &mut vec![first],
+                    *first_contents,
+                    &mut candidates,
+                );
+            }
+        }
+
+        let mut candidates_by_rucksack = vec![Vec::new(); contents.len()];
+        for (candidate_index, candidate) in candidates.iter().enumerate() {
+            for &member in candidate.members.iter() {
+                candidates_by_rucksack[member].push(candidate_index);
+            }
+        }
+
+        Self {
+            analyzer,
+            candidates,
+            candidates_by_rucksack,
+            node_limit,
+        }
+    }
+
+    // adds partners to `members` one at a time, in increasing index order so
+    // that each combination is only tried once
+    fn collect_candidates(
+        group_size: usize,
+        contents: &[ItemSet],
+        badge: usize,
+        partners: &[usize],
+        members: &mut Vec<usize>,
+        common: ItemSet,
+        candidates: &mut Vec<Group>,
+    ) {
+        // a group size of zero can't be filled, and is rejected by `solve`
+        if members.len() >= group_size {
+            // `common` always holds the badge, so this means the badge is the
+            // only thing the whole group shares
+            if members.len() == group_size && common.len() == 1 {
+                candidates.push(Group {
+                    members: members.clone(),
+                    badge,
+                });
+            }
+            return;
+        }
+
+        let still_needed = group_size - members.len();
+        for (i, &partner) in partners.iter().enumerate() {
+            if partners.len() - i < still_needed {
+                break;
+            }
+            members.push(partner);
+            Self::collect_candidates(
+                group_size,
+                contents,
+                badge,
+                &partners[i + 1..],
+                members,
+                common.intersection(contents[partner]),
+                candidates,
+            );
+            members.pop();
+        }
+    }
+
+    // `Ok(None)` means no valid grouping exists, or that none was found
+    // before the node limit was reached
+    pub fn solve(&self) -> Result<Option<Grouping<'a>>, RucksackError> {
+        let group_size = self.analyzer.group_size;
+        let num_rucksacks = self.candidates_by_rucksack.len();
+        if group_size == 0 {
+            return Err(RucksackError::EmptyGroups);
+        }
+        if !num_rucksacks.is_multiple_of(group_size) {
+            return Err(RucksackError::IncompleteGroup {
+                first_line_number: num_rucksacks - num_rucksacks % group_size + 1,
+                len: num_rucksacks % group_size,
+                group_size,
+            });
+        }
+
+        let mut state = SearchState {
+            placed: vec![false; num_rucksacks],
+            live: vec![true; self.candidates.len()],
+            live_counts: self
+                .candidates_by_rucksack
+                .iter()
+                .map(|candidates| candidates.len())
+                .collect(),
+            groups: Vec::with_capacity(num_rucksacks / group_size),
+            first_grouping: None,
+            groupings_found: 0,
+            nodes_visited: 0,
+        };
+        self.search(&mut state);
+
+        let uniqueness = match state.groupings_found {
+            0 => return Ok(None),
+            1 if state.nodes_visited >= self.node_limit => Uniqueness::Unknown,
+            1 => Uniqueness::Unique,
+            _ => Uniqueness::NotUnique,
+        };
+        Ok(state.first_grouping.map(|groups| Grouping {
+            analyzer: self.analyzer,
+            groups: groups
+                .into_iter()
+                .map(|candidate_index| self.candidates[candidate_index].clone())
+                .collect(),
+            uniqueness,
+        }))
+    }
+
+    fn finished(&self, state: &SearchState) -> bool {
+        state.groupings_found >= 2 || state.nodes_visited >= self.node_limit
+    }
+
+    fn search(&self, state: &mut SearchState) {
+        let most_constrained = (0..state.placed.len())
+            .filter(|&rucksack| !state.placed[rucksack])
+            .min_by_key(|&rucksack| state.live_counts[rucksack]);
+        let Some(rucksack) = most_constrained else {
+            state.groupings_found += 1;
+            if state.first_grouping.is_none() {
+                state.first_grouping = Some(state.groups.clone());
+            }
+            return;
+        };
+
+        let options = self.candidates_by_rucksack[rucksack]
+            .iter()
+            .cloned()
+            .filter(|&candidate_index| state.live[candidate_index])
+            .collect::<Vec<_>>();
+        for candidate_index in options {
+            state.nodes_visited += 1;
+            let killed = self.place(state, candidate_index);
+            state.groups.push(candidate_index);
+            self.search(state);
+            state.groups.pop();
+            self.unplace(state, candidate_index, killed);
+            if self.finished(state) {
+                return;
+            }
+        }
+    }
+
+    // marks the candidate's members as placed and every candidate sharing a
+    // member as no longer live; returns those candidates so they can be
+    // revived on backtracking
+    fn place(&self, state: &mut SearchState, candidate_index: usize) -> Vec<usize> {
+        let mut killed = Vec::new();
+        for &member in self.candidates[candidate_index].members.iter() {
+            state.placed[member] = true;
+            for &other in self.candidates_by_rucksack[member].iter() {
+                if state.live[other] {
+                    state.live[other] = false;
+                    for &other_member in self.candidates[other].members.iter() {
+                        state.live_counts[other_member] -= 1;
+                    }
+                    killed.push(other);
+                }
+            }
+        }
+        killed
+    }
+
+    fn unplace(&self, state: &mut SearchState, candidate_index: usize, killed: Vec<usize>) {
+        for other in killed {
+            state.live[other] = true;
+            for &other_member in self.candidates[other].members.iter() {
+                state.live_counts[other_member] += 1;
+            }
+        }
+        for &member in self.candidates[candidate_index].members.iter() {
+            state.placed[member] = false;
+        }
+    }
+}
@@ -1,134 +1,418 @@
-use std::collections::HashSet;
+use std::env;
+use std::error::Error;
+use std::fmt;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::iter::FromIterator;
 
-fn main() {
-    let analyzer = RucksackAnalyzer::new("resources/input_1");
-    let solution_1 = analyzer.common_priority_sum();
-    println!("Part 1 solution: {}", solution_1);
+use anyhow::Result;
 
-    let solution_2 = analyzer.group_badge_priority_sum();
-    println!("Part 2 solution: {}", solution_2);
+mod bench;
+mod grouping;
+mod priority;
+
+use grouping::BadgeGroupSolver;
+use priority::PriorityTable;
+
+fn main() -> Result<()> {
+    let args = env::args().collect::<Vec<_>>();
+    match args.get(1).map(String::as_str) {
+        None => {
+            let analyzer = RucksackAnalyzer::new(
+                "resources/input_1",
+                RucksackAnalyzer::DEFAULT_COMPARTMENT_COUNT,
+                RucksackAnalyzer::DEFAULT_GROUP_SIZE,
+                PriorityTable::default(),
+            )?;
+            let solution_1 = analyzer.common_priority_sum()?;
+            println!("Part 1 solution: {}", solution_1);
+
+            let solution_2 = analyzer.group_badge_priority_sum()?;
+            println!("Part 2 solution: {}", solution_2);
+        }
+        // e.g. `layout 2 3` for two compartments per rucksack, three elves per
+        // group; a priority table file can optionally follow
+        Some("layout") => {
+            let compartment_count = args
+                .get(2)
+                .map(|n| n.parse::<usize>())
+                .transpose()?
+                .unwrap_or(RucksackAnalyzer::DEFAULT_COMPARTMENT_COUNT);
+            let group_size = args
+                .get(3)
+                .map(|n| n.parse::<usize>())
+                .transpose()?
+                .unwrap_or(RucksackAnalyzer::DEFAULT_GROUP_SIZE);
+            let table = match args.get(4) {
+                Some(table_path) => PriorityTable::from_file(table_path)?,
+                None => PriorityTable::default(),
+            };
+            let analyzer =
+                RucksackAnalyzer::new("resources/input_1", compartment_count, group_size, table)?;
+            for rucksack in analyzer.rucksacks.iter() {
+                let common_items = rucksack.common_items(&analyzer.table).collect::<Vec<_>>();
+                println!(
+                    "Line {}: {}",
+                    rucksack.line_number,
+                    common_items
+                        .iter()
+                        .map(|item| item.name)
+                        .collect::<String>()
+                );
+            }
+            println!("Common priority sum: {}", analyzer.common_priority_sum()?);
+            println!(
+                "Group badge priority sum: {}",
+                analyzer.group_badge_priority_sum()?
+            );
+        }
+        // e.g. `discover 3 resources/input_1` for input whose lines may be in
+        // any order; an optional node limit for the search can follow
+        Some("discover") => {
+            let group_size = args
+                .get(2)
+                .map(|n| n.parse::<usize>())
+                .transpose()?
+                .unwrap_or(RucksackAnalyzer::DEFAULT_GROUP_SIZE);
+            let file_path = args
+                .get(3)
+                .map(String::as_str)
+                .unwrap_or("resources/input_1");
+            let node_limit = args
+                .get(4)
+                .map(|n| n.parse::<usize>())
+                .transpose()?
+                .unwrap_or(BadgeGroupSolver::DEFAULT_NODE_LIMIT);
+            let analyzer = RucksackAnalyzer::new(
+                file_path,
+                RucksackAnalyzer::DEFAULT_COMPARTMENT_COUNT,
+                group_size,
+                PriorityTable::default(),
+            )?;
+            match BadgeGroupSolver::new(&analyzer, node_limit).solve()? {
+                Some(grouping) => println!("{}", grouping),
+                None => println!("No grouping found"),
+            }
+        }
+        Some("bench") => {
+            let num_rucksacks = args
+                .get(2)
+                .map(|n| {
+                    n.parse::<usize>()
+                        .expect("Number of rucksacks must be an integer")
+                })
+                .unwrap_or(300_000);
+            bench::run(num_rucksacks);
+        }
+        Some(other) => panic!("Unknown subcommand {}", other),
+    }
+
+    Ok(())
 }
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 struct Item {
     name: char,
+    priority: u32,
 }
 
 impl Item {
-    // can only handle a-z, A-Z
     fn priority(&self) -> u32 {
-        let as_base_36 = self.name.to_digit(36).unwrap();
-        let priority = as_base_36 - 9;
-        if self.name.is_uppercase() {
-            priority + 26
-        } else {
-            priority
+        self.priority
+    }
+}
+
+// A set of items, stored as a bitmask over their indexes in the
+// `PriorityTable`. Intersecting sets is then a few ANDs, and the
+// lowest-priority member is a trailing-zeros count. Tables are kept in
+// ascending priority order, so members always come out in that order too.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct ItemSet([u64; 4]);
+
+impl ItemSet {
+    const CAPACITY: usize = 256;
+    const NONE: Self = Self([0; 4]);
+    const ALL: Self = Self([!0; 4]);
+
+    fn from_indexes(indexes: impl Iterator<Item = usize>) -> Self {
+        let mut bits = [0; 4];
+        for index in indexes {
+            bits[index / 64] |= 1 << (index % 64);
+        }
+        Self(bits)
+    }
+
+    fn len(self) -> u32 {
+        self.0.iter().map(|word| word.count_ones()).sum()
+    }
+
+    fn intersection(self, other: Self) -> Self {
+        let mut bits = self.0;
+        for (word, other_word) in bits.iter_mut().zip(other.0) {
+            *word &= other_word;
+        }
+        Self(bits)
+    }
+
+    fn union(self, other: Self) -> Self {
+        let mut bits = self.0;
+        for (word, other_word) in bits.iter_mut().zip(other.0) {
+            *word |= other_word;
         }
+        Self(bits)
     }
 
-    fn from_char(char: char) -> Self {
-        Self { name: char }
+    // member indexes in ascending order
+    fn indexes(self) -> impl Iterator<Item = usize> {
+        self.0
+            .into_iter()
+            .enumerate()
+            .flat_map(|(word_index, mut word)| {
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        None
+                    } else {
+                        let bit = word.trailing_zeros() as usize;
+                        word &= word - 1; // clear the lowest set bit
+                        Some(word_index * 64 + bit)
+                    }
+                })
+            })
     }
+
+    // the problem guarantees exactly one common item in several places;
+    // anything else is reported with every item that was found
+    fn only_item(self, table: &PriorityTable) -> Result<Item, Vec<Item>> {
+        let mut indexes = self.indexes();
+        match (indexes.next(), indexes.next()) {
+            (Some(index), None) => Ok(table.item(index)),
+            _ => Err(self.indexes().map(|index| table.item(index)).collect()),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum RucksackError {
+    UnevenLine {
+        line_number: usize,
+        len: usize,
+        compartment_count: usize,
+    },
+    UnknownItem {
+        line_number: usize,
+        name: char,
+    },
+    InvalidPriorityTable(String),
+    NoCompartments,
+    EmptyGroups,
+    IncompleteGroup {
+        first_line_number: usize,
+        len: usize,
+        group_size: usize,
+    },
+    NotExactlyOneCommon {
+        line_numbers: Vec<usize>,
+        common_items: Vec<Item>,
+    },
 }
 
+impl fmt::Display for RucksackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnevenLine {
+                line_number,
+                len,
+                compartment_count,
+            } => write!(
+                f,
+                "Line {} has {} items, which can't be split evenly into {} compartments",
+                line_number, len, compartment_count
+            ),
+            Self::UnknownItem { line_number, name } => write!(
+                f,
+                "Line {} contains {:?}, which has no priority in the priority table",
+                line_number, name
+            ),
+            Self::InvalidPriorityTable(reason) => write!(f, "Invalid priority table: {}", reason),
+            Self::NoCompartments => write!(f, "Rucksacks need at least one compartment"),
+            Self::EmptyGroups => write!(f, "Groups need at least one rucksack"),
+            Self::IncompleteGroup {
+                first_line_number,
+                len,
+                group_size,
+            } => write!(
+                f,
+                "Group starting at line {} has only {} rucksacks, expected {}",
+                first_line_number, len, group_size
+            ),
+            Self::NotExactlyOneCommon {
+                line_numbers,
+                common_items,
+            } => write!(
+                f,
+                "Expected exactly one common item for line(s) {:?}, found {:?}",
+                line_numbers,
+                common_items
+                    .iter()
+                    .map(|item| item.name)
+                    .collect::<String>()
+            ),
+        }
+    }
+}
+
+impl Error for RucksackError {}
+
 struct Rucksack {
-    compartment_1: Vec<Item>,
-    compartment_2: Vec<Item>,
+    // one-based, as it appears in the input file
+    line_number: usize,
+    compartments: Vec<ItemSet>,
 }
 
 impl Rucksack {
-    fn common(&self) -> &Item {
-        let compartment_1_set: HashSet<&Item> = HashSet::from_iter(self.compartment_1.iter());
-        let compartment_2_set: HashSet<&Item> = HashSet::from_iter(self.compartment_2.iter());
+    fn new(
+        line_number: usize,
+        line: &str,
+        compartment_count: usize,
+        table: &PriorityTable,
+    ) -> Result<Self, RucksackError> {
+        let items = line
+            .chars()
+            .map(|name| {
+                table
+                    .index_of(name)
+                    .ok_or(RucksackError::UnknownItem { line_number, name })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if items.is_empty() || items.len() % compartment_count != 0 {
+            return Err(RucksackError::UnevenLine {
+                line_number,
+                len: items.len(),
+                compartment_count,
+            });
+        }
+
+        let compartments = items
+            .chunks_exact(items.len() / compartment_count)
+            .map(|compartment| ItemSet::from_indexes(compartment.iter().cloned()))
+            .collect();
+        Ok(Self {
+            line_number,
+            compartments,
+        })
+    }
+
+    fn contents(&self) -> ItemSet {
+        self.compartments
+            .iter()
+            .fold(ItemSet::NONE, |contents, &compartment| {
+                contents.union(compartment)
+            })
+    }
+
+    fn common_set(&self) -> ItemSet {
+        self.compartments
+            .iter()
+            .fold(ItemSet::ALL, |common, &compartment| {
+                common.intersection(compartment)
+            })
+    }
 
-        // a guarantee from the problem is that there will always be one common item
-        let common_items = compartment_1_set
-            .intersection(&compartment_2_set)
-            .map(|&item| item)
-            .collect::<Vec<&Item>>();
+    // items found in every compartment, in ascending priority order
+    fn common_items<'a>(&self, table: &'a PriorityTable) -> impl Iterator<Item = Item> + 'a {
+        self.common_set().indexes().map(|index| table.item(index))
+    }
 
-        common_items[0]
+    fn common(&self, table: &PriorityTable) -> Result<Item, RucksackError> {
+        self.common_set().only_item(table).map_err(|common_items| {
+            RucksackError::NotExactlyOneCommon {
+                line_numbers: vec![self.line_number],
+                common_items,
+            }
+        })
     }
 }
 
 struct RucksackAnalyzer {
     rucksacks: Vec<Rucksack>,
+    group_size: usize,
+    table: PriorityTable,
 }
 
 impl RucksackAnalyzer {
-    fn new(file_path: &str) -> Self {
-        let file = File::open(file_path).unwrap();
+    const DEFAULT_COMPARTMENT_COUNT: usize = 2;
+    const DEFAULT_GROUP_SIZE: usize = 3;
+
+    fn new(
+        file_path: &str,
+        compartment_count: usize,
+        group_size: usize,
+        table: PriorityTable,
+    ) -> Result<Self> {
+        let file = File::open(file_path)?;
         let reader = BufReader::new(file);
+        let lines = reader.lines().collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_lines(
+            lines.into_iter(),
+            compartment_count,
+            group_size,
+            table,
+        )?)
+    }
 
-        let mut rucksacks = Vec::new();
-        for line in reader.lines() {
-            let line_content = line.unwrap();
-            let line_len = line_content.len();
-            let chars = line_content.chars();
-            let first_half = chars
-                .clone()
-                .take(line_len / 2)
-                .map(|char| Item::from_char(char))
-                .collect::<Vec<_>>();
-            let second_half = chars
-                .skip(line_len / 2)
-                .take(line_len / 2)
-                .map(|char| Item::from_char(char))
-                .collect::<Vec<_>>();
-            rucksacks.push(Rucksack {
-                compartment_1: first_half.clone(),
-                compartment_2: second_half.clone(),
-            });
+    fn from_lines(
+        lines: impl Iterator<Item = String>,
+        compartment_count: usize,
+        group_size: usize,
+        table: PriorityTable,
+    ) -> Result<Self, RucksackError> {
+        if compartment_count == 0 {
+            return Err(RucksackError::NoCompartments);
+        }
+        if group_size == 0 {
+            return Err(RucksackError::EmptyGroups);
         }
 
-        Self { rucksacks }
+        let rucksacks = lines
+            .enumerate()
+            .map(|(i, line)| Rucksack::new(i + 1, &line, compartment_count, &table))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            rucksacks,
+            group_size,
+            table,
+        })
     }
 
-    fn common_priority_sum(&self) -> u32 {
+    fn common_priority_sum(&self) -> Result<u32, RucksackError> {
         self.rucksacks
             .iter()
-            .map(|rucksack| rucksack.common().priority())
+            .map(|rucksack| Ok(rucksack.common(&self.table)?.priority()))
             .sum()
     }
 
-    fn group_badge_priority_sum(&self) -> u32 {
-        let mut running_priority = 0;
-        let mut rucksack_triad = Vec::new();
-        self.rucksacks.iter().for_each(|rucksack| {
-            rucksack_triad.push(rucksack);
-
-            if rucksack_triad.len() == 3 {
-                let rucksack_content_sets: Vec<HashSet<Item>> = rucksack_triad
-                    .iter()
-                    .map(|&rucksack| {
-                        let mut combined = rucksack.compartment_1.clone();
-                        combined.extend(rucksack.compartment_2.clone());
-                        HashSet::from_iter(combined)
-                    })
-                    .collect::<Vec<HashSet<Item>>>();
-
-                let first = &rucksack_content_sets[0];
-                let second = &rucksack_content_sets[1];
-                let third = &rucksack_content_sets[2];
-                let first_second = first
-                    .intersection(second)
-                    .map(|item| item.clone())
-                    .collect::<HashSet<_>>();
-                let full_inter = first_second.intersection(third).collect::<Vec<_>>();
-                let common_item = full_inter[0];
-                running_priority += common_item.priority();
-                rucksack_triad.clear();
-            }
-        });
+    fn group_badge_priority_sum(&self) -> Result<u32, RucksackError> {
+        self.rucksacks
+            .chunks(self.group_size)
+            .map(|group| {
+                if group.len() < self.group_size {
+                    return Err(RucksackError::IncompleteGroup {
+                        first_line_number: group[0].line_number,
+                        len: group.len(),
+                        group_size: self.group_size,
+                    });
+                }
 
-        running_priority
+                let badges = group.iter().fold(ItemSet::ALL, |common, rucksack| {
+                    common.intersection(rucksack.contents())
+                });
+                let badge = badges.only_item(&self.table).map_err(|common_items| {
+                    RucksackError::NotExactlyOneCommon {
+                        line_numbers: group.iter().map(|rucksack| rucksack.line_number).collect(),
+                        common_items,
+                    }
+                })?;
+                Ok(badge.priority())
+            })
+            .sum()
     }
 }
-
-// fix clippy warning about explicit closure for copying elements
-// try to reduce use of clone
-// try to get rid of very weird map I needed to add an extra &
-// generally see more idiomatic solutions, there has to be a cleaner way to wrangle these sets
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::fs;
+
+use crate::{Item, ItemSet, RucksackError};
+
+// Maps each known item to its priority. Items are stored in ascending
+// priority order, and an item's position in that order is the bit it
+// occupies in an `ItemSet`.
+pub struct PriorityTable {
+    items: Vec<Item>,
+    // ASCII items get an array lookup, since that covers every real input
+    // and hashing each char would dominate parsing time
+    ascii_indexes: [Option<usize>; 128],
+    indexes: HashMap<char, usize>,
+}
+
+impl PriorityTable {
+    // the puzzle's own table: a-z have priorities 1-26, A-Z have 27-52
+    const DEFAULT_ALPHABET: &'static str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+    fn new(mut items: Vec<Item>) -> Result<Self, RucksackError> {
+        if items.len() > ItemSet::CAPACITY {
+            return Err(RucksackError::InvalidPriorityTable(format!(
+                "{} items given, but at most {} are supported",
+                items.len(),
+                ItemSet::CAPACITY
+            )));
+        }
+
+        items.sort_by_key(|item| (item.priority, item.name));
+        let mut indexes = HashMap::new();
+        for (index, item) in items.iter().enumerate() {
+            if indexes.insert(item.name, index).is_some() {
+                return Err(RucksackError::InvalidPriorityTable(format!(
+                    "{:?} is listed more than once",
+                    item.name
+                )));
+            }
+        }
+
+        let mut ascii_indexes = [None; 128];
+        for (&name, &index) in indexes.iter() {
+            if name.is_ascii() {
+                ascii_indexes[name as usize] = Some(index);
+            }
+        }
+
+        Ok(Self {
+            items,
+            ascii_indexes,
+            indexes,
+        })
+    }
+
+    // the first char of `alphabet` has priority 1, the second has priority 2,
+    // and so on
+    pub fn from_alphabet(alphabet: &str) -> Result<Self, RucksackError> {
+        Self::new(
+            alphabet
+                .chars()
+                .zip(1..)
+                .map(|(name, priority)| Item { name, priority })
+                .collect(),
+        )
+    }
+
+    // example file line: "7 35", giving the item `7` a priority of 35
+    pub fn from_file(file_path: &str) -> anyhow::Result<Self> {
+        let file_content = fs::read_to_string(file_path)?;
+        let mut items = Vec::new();
+        for (i, line) in file_content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let mut split = line.split_whitespace();
+            let (name, priority) = (split.next(), split.next());
+            let mut name_chars = name.unwrap_or("").chars();
+            let item = match (name_chars.next(), name_chars.next(), priority, split.next()) {
+                (Some(name), None, Some(priority), None) => priority
+                    .parse::<u32>()
+                    .ok()
+                    .map(|priority| Item { name, priority }),
+                _ => None,
+            };
+            match item {
+                Some(item) => items.push(item),
+                None => {
+                    return Err(RucksackError::InvalidPriorityTable(format!(
+                        "line {} should be a single item and a priority, found {:?}",
+                        i + 1,
+                        line
+                    ))
+                    .into())
+                }
+            }
+        }
+
+        Ok(Self::new(items)?)
+    }
+
+    pub fn index_of(&self, name: char) -> Option<usize> {
+        if name.is_ascii() {
+            self.ascii_indexes[name as usize]
+        } else {
+            self.indexes.get(&name).cloned()
+        }
+    }
+
+    pub fn item(&self, index: usize) -> Item {
+        self.items[index]
+    }
+}
+
+impl Default for PriorityTable {
+    fn default() -> Self {
+        Self::from_alphabet(Self::DEFAULT_ALPHABET).unwrap()
+    }
+}
@@ -0,0 +1,73 @@
+use crate::interval_set::IntervalSet;
+use crate::Pair;
+
+// Elves in one assignment group are the nodes, and an edge joins any two
+// elves whose assignments overlap. Elves are identified by their zero-based
+// position in the group.
+pub struct ConflictGraph<'a> {
+    elves: &'a [Pair],
+    edges: Vec<(usize, usize)>,
+}
+
+impl<'a> ConflictGraph<'a> {
+    pub fn new(elves: &'a [Pair]) -> Self {
+        let mut by_start = (0..elves.len()).collect::<Vec<_>>();
+        by_start.sort_by_key(|&elf| elves[elf].start);
+
+        // once an elf starts after `elf` ends, every later one does too
+        let mut edges = Vec::new();
+        for (i, &elf) in by_start.iter().enumerate() {
+            for &other in by_start[i + 1..].iter() {
+                if elves[other].start > elves[elf].end {
+                    break;
+                }
+                edges.push((elf.min(other), elf.max(other)));
+            }
+        }
+        edges.sort();
+
+        Self { elves, edges }
+    }
+
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    // The fewest elves to remove so that no two remaining assignments
+    // overlap. Keeping as many as possible is the classic interval
+    // scheduling problem: repeatedly keeping the assignment that ends
+    // earliest, among those that start after the last kept one ends, is
+    // optimal.
+    pub fn min_removals(&self) -> Vec<usize> {
+        let mut by_end = (0..self.elves.len()).collect::<Vec<_>>();
+        by_end.sort_by_key(|&elf| (self.elves[elf].end, self.elves[elf].start));
+
+        let mut removed = Vec::new();
+        let mut last_kept_end = None;
+        for elf in by_end {
+            match last_kept_end {
+                Some(end) if self.elves[elf].start <= end => removed.push(elf),
+                _ => last_kept_end = Some(self.elves[elf].end),
+            }
+        }
+        removed.sort();
+        removed
+    }
+
+    // elves whose every section is also covered by some other elf in the group
+    pub fn redundant(&self) -> Vec<usize> {
+        (0..self.elves.len())
+            .filter(|&elf| {
+                let others = IntervalSet::new(
+                    self.elves
+                        .iter()
+                        .enumerate()
+                        .filter(|&(other, _)| other != elf)
+                        .map(|(_, &pair)| pair),
+                );
+                let assignment = IntervalSet::new([self.elves[elf]]);
+                assignment.difference(&others).is_empty()
+            })
+            .collect()
+    }
+}
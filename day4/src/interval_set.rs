@@ -0,0 +1,196 @@
+use std::fmt;
+
+use crate::Pair;
+
+// A set of sections, kept as sorted, disjoint ranges. Sections are whole
+// numbers, so ranges that merely touch (like 2-4 and 5-7) are merged too.
+// Reversed pairs like 5-3 cover nothing, so they're left out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<Pair>,
+}
+
+impl IntervalSet {
+    pub fn new(pairs: impl IntoIterator<Item = Pair>) -> Self {
+        let mut pairs = pairs
+            .into_iter()
+            .filter(|pair| !pair.is_empty())
+            .collect::<Vec<_>>();
+        pairs.sort_by_key(|pair| pair.start);
+
+        let mut ranges: Vec<Pair> = Vec::with_capacity(pairs.len());
+        for pair in pairs {
+            match ranges.last_mut() {
+                Some(last) if pair.start as u64 <= last.end as u64 + 1 => {
+                    last.end = last.end.max(pair.end);
+                }
+                _ => ranges.push(pair),
+            }
+        }
+
+        Self { ranges }
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(self.ranges.iter().chain(other.ranges.iter()).cloned())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (ours, theirs) = (self.ranges[i], other.ranges[j]);
+            if let Some(overlap) = ours.overlap(&theirs) {
+                ranges.push(overlap);
+            }
+            // whichever range ends first can't overlap anything further along
+            if ours.end < theirs.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for &range in self.ranges.iter() {
+            let mut remaining = Some(range);
+            // skip ranges of `other` that end before this one starts
+            while j < other.ranges.len() && other.ranges[j].end < range.start {
+                j += 1;
+            }
+
+            let mut k = j;
+            while let (Some(current), Some(&removed)) = (remaining, other.ranges.get(k)) {
+                if removed.start > current.end {
+                    break;
+                }
+                if removed.start > current.start {
+                    ranges.push(Pair {
+                        start: current.start,
+                        end: removed.start - 1,
+                    });
+                }
+                remaining = if removed.end < current.end {
+                    Some(Pair {
+                        start: removed.end + 1,
+                        end: current.end,
+                    })
+                } else {
+                    None
+                };
+                k += 1;
+            }
+
+            if let Some(current) = remaining {
+                ranges.push(current);
+            }
+        }
+
+        Self { ranges }
+    }
+
+    // how many sections are in the set
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|range| range.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // the sections between the lowest and highest members that aren't
+    // members themselves
+    pub fn gaps(&self) -> Self {
+        let ranges = self
+            .ranges
+            .windows(2)
+            .map(|window| Pair {
+                start: window[0].end + 1,
+                end: window[1].start - 1,
+            })
+            .collect();
+        Self { ranges }
+    }
+}
+
+impl fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|range| range.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{{{}}}", ranges.join(", "))
+    }
+}
+
+// Sweeps across every pair's endpoints to find the section covered by the
+// most pairs, returning that section and how many pairs cover it. Ties go
+// to the lowest section.
+pub fn max_overlap<'a>(pairs: impl IntoIterator<Item = &'a Pair>) -> Option<(u32, usize)> {
+    // a pair covers `start` through `end` inclusive, so it stops counting at
+    // `end + 1`; u64 keeps that from overflowing
+    let mut events = Vec::new();
+    for pair in pairs.into_iter().filter(|pair| !pair.is_empty()) {
+        events.push((pair.start as u64, 1));
+        events.push((pair.end as u64 + 1, -1));
+    }
+    // at equal positions, endings sort first, since -1 < 1
+    events.sort();
+
+    let mut best: Option<(u32, usize)> = None;
+    let mut depth = 0_i64;
+    for (position, change) in events {
+        depth += change;
+        if change > 0 && best.is_none_or(|(_, multiplicity)| depth as usize > multiplicity) {
+            best = Some((position as u32, depth as usize));
+        }
+    }
+
+    best
+}
+
+// The fewest pairs that together still cover every section any pair covers,
+// returned as indexes into `pairs`. Greedy is optimal here: starting from
+// the first section not yet covered, always take whichever pair starting at
+// or before it reaches furthest.
+pub fn minimal_cover(pairs: &[Pair]) -> Vec<usize> {
+    let mut by_start = (0..pairs.len()).collect::<Vec<_>>();
+    by_start.sort_by_key(|&i| pairs[i].start);
+
+    let mut chosen = Vec::new();
+    let mut covered_through: Option<u64> = None;
+    let mut next = 0;
+    while next < by_start.len() {
+        let next_start = pairs[by_start[next]].start as u64;
+        // the first section still needing cover; if nothing reaches it, the
+        // next pair begins a new stretch of coverage
+        let frontier = match covered_through {
+            Some(end) if next_start <= end + 1 => end + 1,
+            _ => next_start,
+        };
+
+        let mut furthest: Option<usize> = None;
+        while next < by_start.len() && pairs[by_start[next]].start as u64 <= frontier {
+            let candidate = by_start[next];
+            if furthest.is_none_or(|best| pairs[candidate].end > pairs[best].end) {
+                furthest = Some(candidate);
+            }
+            next += 1;
+        }
+
+        // pairs that end before the frontier add nothing new
+        if let Some(best) = furthest.filter(|&best| pairs[best].end as u64 >= frontier) {
+            chosen.push(best);
+            covered_through = Some(pairs[best].end as u64);
+        }
+    }
+
+    chosen.sort();
+    chosen
+}
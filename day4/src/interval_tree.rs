@@ -0,0 +1,80 @@
+use crate::Pair;
+
+// A static interval tree for "who covers section s" and "who overlaps range
+// a-b" queries. Entries are sorted by start, and the sorted array doubles as
+// an implicit balanced binary tree: the middle entry of any slice is its
+// root, and the halves on either side are its subtrees. Each node also
+// records the latest end anywhere in its subtree, so whole subtrees that
+// finish before the query starts can be skipped. Queries take O(log n + k)
+// for k matches.
+pub struct IntervalTree<T> {
+    entries: Vec<(Pair, T)>,
+    // `max_ends[i]` is the latest end in the subtree rooted at entry `i`
+    max_ends: Vec<u32>,
+}
+
+impl<T> IntervalTree<T> {
+    pub fn new(mut entries: Vec<(Pair, T)>) -> Self {
+        entries.sort_by_key(|(pair, _)| (pair.start, pair.end));
+        let mut max_ends = vec![0; entries.len()];
+        Self::fill_max_ends(&entries, &mut max_ends, 0, entries.len());
+        Self { entries, max_ends }
+    }
+
+    // returns the latest end in `lo..hi`, the subtree rooted at its middle
+    fn fill_max_ends(entries: &[(Pair, T)], max_ends: &mut [u32], lo: usize, hi: usize) -> u32 {
+        if lo >= hi {
+            return 0;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let left = Self::fill_max_ends(entries, max_ends, lo, mid);
+        let right = Self::fill_max_ends(entries, max_ends, mid + 1, hi);
+        max_ends[mid] = entries[mid].0.end.max(left).max(right);
+        max_ends[mid]
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    // every entry covering `section`
+    pub fn stab(&self, section: u32) -> Vec<&(Pair, T)> {
+        self.overlapping(Pair {
+            start: section,
+            end: section,
+        })
+    }
+
+    // every entry sharing at least one section with `range`, ordered by start
+    pub fn overlapping(&self, range: Pair) -> Vec<&(Pair, T)> {
+        let mut found = Vec::new();
+        self.collect_overlapping(range, 0, self.entries.len(), &mut found);
+        found
+    }
+
+    fn collect_overlapping<'a>(
+        &'a self,
+        range: Pair,
+        lo: usize,
+        hi: usize,
+        found: &mut Vec<&'a (Pair, T)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        if self.max_ends[mid] < range.start {
+            return; // everything in this subtree ends too early
+        }
+
+        self.collect_overlapping(range, lo, mid, found);
+        let entry = &self.entries[mid];
+        if entry.0.start > range.end {
+            return; // this entry and everything after it start too late
+        }
+        if entry.0.end >= range.start {
+            found.push(entry);
+        }
+        self.collect_overlapping(range, mid + 1, hi, found);
+    }
+}
@@ -1,14 +1,167 @@
+use std::env;
+use std::fmt;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader};
+
+mod conflict_graph;
+mod interval_set;
+mod interval_tree;
+
+use conflict_graph::ConflictGraph;
+use interval_set::IntervalSet;
+use interval_tree::IntervalTree;
 
 fn main() {
+    let args = env::args().collect::<Vec<_>>();
     let analyzer = RangeAnalyzer::new("resources/input_1");
-    let solution_1 = analyzer.count_full_overlaps();
-    println!("Part 1 solution: {}", solution_1);
-    let solution_2 = analyzer.count_partial_overlaps();
-    println!("Part 2 solution: {}", solution_2);
+    match args.get(1).map(String::as_str) {
+        None => {
+            let solution_1 = analyzer.count_full_overlaps();
+            println!("Part 1 solution: {}", solution_1);
+            let solution_2 = analyzer.count_partial_overlaps();
+            println!("Part 2 solution: {}", solution_2);
+        }
+        Some("coverage") => {
+            let all = analyzer.coverage();
+            // "first" elves are listed first on their line; "second" elves are
+            // all the rest
+            let first = IntervalSet::new(analyzer.groups.iter().map(|group| group.elves[0]));
+            let second = IntervalSet::new(
+                analyzer
+                    .groups
+                    .iter()
+                    .flat_map(|group| group.elves[1..].iter().cloned()),
+            );
+            println!("Covered: {} ({} sections)", all, all.len());
+            let gaps = all.gaps();
+            if gaps.is_empty() {
+                println!("Uncovered: none");
+            } else {
+                println!("Uncovered: {} ({} sections)", gaps, gaps.len());
+            }
+            println!(
+                "Covered by both first and second elves: {}",
+                first.intersection(&second)
+            );
+            println!("Covered by first elves only: {}", first.difference(&second));
+            println!(
+                "Covered by second elves only: {}",
+                second.difference(&first)
+            );
+            println!("Covered by either: {}", first.union(&second));
+            if let Some((section, multiplicity)) = analyzer.max_overlap() {
+                println!(
+                    "Most overlapped: section {}, covered by {} elves",
+                    section, multiplicity
+                );
+            }
+        }
+        // elves are numbered from one within their line
+        Some("overlaps") => {
+            for (i, group) in analyzer.groups.iter().enumerate() {
+                let overlaps = group
+                    .overlaps()
+                    .iter()
+                    .map(|(first, second, overlap)| {
+                        format!("elves {} and {}: {}", first + 1, second + 1, overlap)
+                    })
+                    .collect::<Vec<_>>();
+                if overlaps.is_empty() {
+                    println!("Line {}: none", i + 1);
+                } else {
+                    println!("Line {}: {}", i + 1, overlaps.join("; "));
+                }
+            }
+        }
+        Some("conflicts") => {
+            let mut total_removals = 0;
+            for (i, group) in analyzer.groups.iter().enumerate() {
+                let graph = ConflictGraph::new(&group.elves);
+                let one_based =
+                    |elves: &[usize]| elves.iter().map(|elf| elf + 1).collect::<Vec<_>>();
+                let edges = graph
+                    .edges()
+                    .iter()
+                    .map(|&(first, second)| (first + 1, second + 1))
+                    .collect::<Vec<_>>();
+                let removals = graph.min_removals();
+                total_removals += removals.len();
+                println!(
+                    "Line {}: conflicts {:?}, remove {:?}, redundant {:?}",
+                    i + 1,
+                    edges,
+                    one_based(&removals),
+                    one_based(&graph.redundant())
+                );
+            }
+            println!("Total elves to remove: {}", total_removals);
+        }
+        Some("cover") => {
+            let pairs = analyzer.pairs().cloned().collect::<Vec<_>>();
+            let elf_ids = analyzer.elf_ids();
+            let chosen = interval_set::minimal_cover(&pairs);
+            println!(
+                "{} of {} elves cover all {} covered sections:",
+                chosen.len(),
+                pairs.len(),
+                analyzer.coverage().len()
+            );
+            for &i in chosen.iter() {
+                let (line_number, elf_number) = elf_ids[i];
+                println!("  line {}, elf {}: {}", line_number, elf_number, pairs[i]);
+            }
+
+            let dispensable = (0..pairs.len())
+                .filter(|i| chosen.binary_search(i).is_err())
+                .map(|i| elf_ids[i])
+                .collect::<Vec<_>>();
+            println!(
+                "{} elves are dispensable: {:?}",
+                dispensable.len(),
+                dispensable
+            );
+        }
+        // answers "5123" (who covers section 5123) or "100-200" (who overlaps
+        // sections 100-200); queries come from the remaining arguments, or
+        // from stdin one per line if there are none
+        Some("query") => {
+            let index = analyzer.index();
+            println!("Indexed {} assignments", index.len());
+            let run_query = |query: &str| {
+                let matches = if query.contains('-') {
+                    Pair::parse(query).map(|range| index.overlapping(range))
+                } else {
+                    query.parse().ok().map(|section| index.stab(section))
+                };
+                let Some(matches) = matches else {
+                    println!(
+                        "{}: expected a section like 5123 or a range like 100-200",
+                        query
+                    );
+                    return;
+                };
+                println!("{}: {} elves", query, matches.len());
+                for (pair, (line_number, elf_number)) in matches {
+                    println!("  line {}, elf {}: {}", line_number, elf_number, pair);
+                }
+            };
+
+            if args.len() > 2 {
+                args[2..].iter().for_each(|query| run_query(query));
+            } else {
+                for line in io::stdin().lock().lines() {
+                    let line_content = line.unwrap();
+                    if !line_content.trim().is_empty() {
+                        run_query(line_content.trim());
+                    }
+                }
+            }
+        }
+        Some(other) => panic!("Unknown subcommand {}", other),
+    }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct Pair {
     start: u32,
     end: u32,
@@ -24,39 +177,81 @@ impl Pair {
             end: end.parse().unwrap(),
         }
     }
+
+    // like `new`, but `None` for anything other than "start-end" with
+    // start <= end
+    fn parse(input: &str) -> Option<Self> {
+        let (start, end) = input.split_once('-')?;
+        let (start, end) = (start.trim().parse().ok()?, end.trim().parse().ok()?);
+        (start <= end).then_some(Self { start, end })
+    }
+
+    // the sections both pairs cover, if any
+    fn overlap(&self, other: &Self) -> Option<Self> {
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+        if start <= end {
+            Some(Self { start, end })
+        } else {
+            None
+        }
+    }
+
+    // how many sections the pair covers; none if it's reversed, like 5-3
+    fn len(&self) -> u64 {
+        (self.end as u64 + 1).saturating_sub(self.start as u64)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.start > self.end
+    }
 }
 
-struct RangePair {
-    first: Pair,
-    second: Pair,
+impl fmt::Display for Pair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
 }
 
-impl RangePair {
+// all the elves listed on one line of the input
+struct AssignmentGroup {
+    elves: Vec<Pair>,
+}
+
+impl AssignmentGroup {
+    // example input: "2-4,3-5" or "2-4,3-5,9-12"
+    fn new(file_line: &str) -> Self {
+        Self {
+            elves: file_line.split(',').map(Pair::new).collect(),
+        }
+    }
+
+    // whether any elf's assignment fully contains another's
     fn full_overlap(&self) -> bool {
-        let first_contains_second =
-            self.first.start <= self.second.start && self.first.end >= self.second.end;
-        let second_contains_first =
-            self.second.start <= self.first.start && self.second.end >= self.first.end;
-        first_contains_second || second_contains_first
+        self.overlaps().iter().any(|&(first, second, overlap)| {
+            overlap == self.elves[first] || overlap == self.elves[second]
+        })
     }
 
     fn any_overlap(&self) -> bool {
-        !(self.first.start > self.second.end || self.second.start > self.first.end)
+        !ConflictGraph::new(&self.elves).edges().is_empty()
     }
 
-    // example input: "2-4,3-5"
-    fn new(file_line: &str) -> Self {
-        let mut split = file_line.split(",");
-        let (first, second) = (split.next().unwrap(), split.next().unwrap());
-        Self {
-            first: Pair::new(first),
-            second: Pair::new(second),
-        }
+    // the exact sections shared by each overlapping pair of elves
+    fn overlaps(&self) -> Vec<(usize, usize, Pair)> {
+        ConflictGraph::new(&self.elves)
+            .edges()
+            .iter()
+            .map(|&(first, second)| {
+                let overlap = self.elves[first].overlap(&self.elves[second]).unwrap();
+                (first, second, overlap)
+            })
+            .collect()
     }
 }
 
 struct RangeAnalyzer {
-    range_pairs: Vec<RangePair>,
+    groups: Vec<AssignmentGroup>,
 }
 
 impl RangeAnalyzer {
@@ -64,27 +259,54 @@ impl RangeAnalyzer {
         let file = File::open(file_path).unwrap();
         let reader = BufReader::new(file);
 
-        let mut range_pairs = Vec::new();
+        let mut groups = Vec::new();
         for line in reader.lines() {
-            range_pairs.push(RangePair::new(&line.unwrap()));
+            groups.push(AssignmentGroup::new(&line.unwrap()));
         }
 
-        Self { range_pairs }
+        Self { groups }
     }
 
     fn count_full_overlaps(&self) -> u32 {
-        self.range_pairs
+        self.groups
             .iter()
-            .map(|pair| if pair.full_overlap() { 1 } else { 0 })
+            .map(|group| if group.full_overlap() { 1 } else { 0 })
             .sum()
     }
 
     fn count_partial_overlaps(&self) -> u32 {
-        self.range_pairs
+        self.groups
             .iter()
-            .map(|pair| if pair.any_overlap() { 1 } else { 0 })
+            .map(|group| if group.any_overlap() { 1 } else { 0 })
             .sum()
     }
+
+    // every elf's assignment, in file order
+    fn pairs(&self) -> impl Iterator<Item = &Pair> {
+        self.groups.iter().flat_map(|group| group.elves.iter())
+    }
+
+    fn coverage(&self) -> IntervalSet {
+        IntervalSet::new(self.pairs().cloned())
+    }
+
+    // identifies each elf, in the same order as `pairs`, by its one-based
+    // line number and its one-based position on that line
+    fn elf_ids(&self) -> Vec<(usize, usize)> {
+        self.groups
+            .iter()
+            .enumerate()
+            .flat_map(|(i, group)| (0..group.elves.len()).map(move |j| (i + 1, j + 1)))
+            .collect()
+    }
+
+    fn index(&self) -> IntervalTree<(usize, usize)> {
+        IntervalTree::new(self.pairs().cloned().zip(self.elf_ids()).collect())
+    }
+
+    fn max_overlap(&self) -> Option<(u32, usize)> {
+        interval_set::max_overlap(self.pairs())
+    }
 }
 
 // find a more idomatic way to count than having map convert to int
@@ -0,0 +1,118 @@
+use std::collections::VecDeque;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::Command;
+
+// A model of crane that carries out rearrangement commands. Stacks are
+// passed with their top crate at the front. Commands are checked against
+// the stacks before they reach a crane, so both stack numbers are in range;
+// a command asking for more crates than its source stack holds moves as
+// many as there are.
+pub trait Crane {
+    fn name(&self) -> String;
+    fn execute(&self, stacks: &mut [VecDeque<String>], command: &Command);
+}
+
+// picks a crane by name, e.g. "9000", "9001", "limited:3" or "bottom"
+pub fn crane_from_name(name: &str) -> Result<Box<dyn Crane>> {
+    Ok(match name.split_once(':') {
+        None if name == "9000" => Box::new(CrateMover9000),
+        None if name == "9001" => Box::new(CrateMover9001),
+        None if name == "bottom" => Box::new(BottomLifter),
+        Some(("limited", max_lift)) => {
+            let max_lift = max_lift
+                .parse()
+                .map_err(|_| anyhow!("Max lift must be an integer, not {:?}", max_lift))?;
+            // a crane that can't lift anything would never finish a move
+            if max_lift == 0 {
+                bail!("Max lift must be at least 1");
+            }
+            Box::new(LimitedLift { max_lift })
+        }
+        _ => bail!("Unknown crane {}", name),
+    })
+}
+
+// takes `num_crates` off the top of `stack`, top crate first
+fn lift_from_top(stack: &mut VecDeque<String>, num_crates: usize) -> VecDeque<String> {
+    let num_crates = num_crates.min(stack.len());
+    let rest = stack.split_off(num_crates);
+    std::mem::replace(stack, rest)
+}
+
+// sets `lifted` on top of `stack` without changing its order
+fn place_on_top(stack: &mut VecDeque<String>, lifted: VecDeque<String>) {
+    for moved_crate in lifted.into_iter().rev() {
+        stack.push_front(moved_crate);
+    }
+}
+
+// moves one crate at a time, so a moved block ends up in reverse order
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        String::from("CrateMover 9000")
+    }
+
+    fn execute(&self, stacks: &mut [VecDeque<String>], command: &Command) {
+        for _ in 0..command.num_crates {
+            if let Some(removed_crate) = stacks[command.source_index()].pop_front() {
+                stacks[command.dest_index()].push_front(removed_crate);
+            }
+        }
+    }
+}
+
+// moves the whole block at once, keeping its order
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        String::from("CrateMover 9001")
+    }
+
+    fn execute(&self, stacks: &mut [VecDeque<String>], command: &Command) {
+        let lifted = lift_from_top(&mut stacks[command.source_index()], command.num_crates);
+        place_on_top(&mut stacks[command.dest_index()], lifted);
+    }
+}
+
+// like the 9001, but can lift at most `max_lift` crates at once, so larger
+// moves are split into several trips
+pub struct LimitedLift {
+    pub max_lift: usize,
+}
+
+impl Crane for LimitedLift {
+    fn name(&self) -> String {
+        format!("limited lift ({} crates)", self.max_lift)
+    }
+
+    fn execute(&self, stacks: &mut [VecDeque<String>], command: &Command) {
+        let mut remaining = command.num_crates;
+        while remaining > 0 && !stacks[command.source_index()].is_empty() {
+            let trip = remaining.min(self.max_lift);
+            let lifted = lift_from_top(&mut stacks[command.source_index()], trip);
+            place_on_top(&mut stacks[command.dest_index()], lifted);
+            remaining -= trip;
+        }
+    }
+}
+
+// pulls the block from the bottom of the source stack instead of the top,
+// setting it on top of the destination in the same order
+pub struct BottomLifter;
+
+impl Crane for BottomLifter {
+    fn name(&self) -> String {
+        String::from("bottom lifter")
+    }
+
+    fn execute(&self, stacks: &mut [VecDeque<String>], command: &Command) {
+        let source = &mut stacks[command.source_index()];
+        let lifted = source.split_off(source.len() - command.num_crates.min(source.len()));
+        place_on_top(&mut stacks[command.dest_index()], lifted);
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use crate::Command;
+
+struct Node {
+    label: String,
+    below: Option<Rc<Node>>,
+}
+
+// An immutable stack of crates, top first, stored as a linked list so that
+// different versions of a stack can share everything below the crates that
+// changed between them.
+#[derive(Clone, Default)]
+pub struct PersistentStack {
+    top: Option<Rc<Node>>,
+    len: usize,
+}
+
+impl PersistentStack {
+    fn from_deque(stack: &VecDeque<String>) -> Self {
+        Self::default().with_pushed(stack.iter())
+    }
+
+    // `labels` are given top first
+    fn with_pushed<'a>(&self, labels: impl DoubleEndedIterator<Item = &'a String>) -> Self {
+        let mut pushed = self.clone();
+        for label in labels.rev() {
+            pushed = Self {
+                top: Some(Rc::new(Node {
+                    label: label.clone(),
+                    below: pushed.top,
+                })),
+                len: pushed.len + 1,
+            };
+        }
+        pushed
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &String> {
+        let mut node = self.top.as_deref();
+        std::iter::from_fn(move || {
+            let current = node?;
+            node = current.below.as_deref();
+            Some(&current.label)
+        })
+    }
+
+    // the version of this stack with its top `num_popped` crates removed
+    fn popped(&self, num_popped: usize) -> Self {
+        let mut popped = self.clone();
+        for _ in 0..num_popped {
+            let top = popped.top.unwrap();
+            popped = Self {
+                top: top.below.clone(),
+                len: popped.len - 1,
+            };
+        }
+        popped
+    }
+
+    // Builds the next version of this stack, whose contents are now
+    // `current`, sharing as much of the bottom as the two have in common.
+    // Cranes only ever touch the top of a destination stack, so usually only
+    // the newly arrived crates need new nodes.
+    fn advanced(&self, current: &VecDeque<String>) -> Self {
+        // line the two up from the bottom, then find how far up they agree
+        let common_len = self.len.min(current.len());
+        let ours = self.popped(self.len - common_len);
+        let mut shared_len = common_len;
+        for (i, (our_label, current_label)) in ours
+            .iter()
+            .zip(current.iter().skip(current.len() - common_len))
+            .enumerate()
+        {
+            if our_label != current_label {
+                shared_len = common_len - i - 1;
+            }
+        }
+
+        let shared = ours.popped(common_len - shared_len);
+        shared.with_pushed(current.iter().take(current.len() - shared_len))
+    }
+
+    pub fn to_deque(&self) -> VecDeque<String> {
+        self.iter().cloned().collect()
+    }
+}
+
+// Every stack at one point in the rearrangement. Cloning one only copies a
+// pointer per stack.
+pub type Snapshot = Vec<PersistentStack>;
+
+pub struct Step {
+    pub command: Command,
+    // the crates that arrived on the destination stack, top first; empty if
+    // the command was skipped
+    pub moved: Vec<String>,
+}
+
+// Records each executed command along with a snapshot of the stacks after
+// it ran. `snapshots[n]` is the state after the first `n` steps, so
+// `snapshots[0]` is the starting drawing. Undoing only moves the cursor back,
+// so the undone steps can be redone until a new command is recorded.
+pub struct History {
+    steps: Vec<Step>,
+    snapshots: Vec<Snapshot>,
+    cursor: usize,
+}
+
+impl History {
+    pub fn new(stacks: &[VecDeque<String>]) -> Self {
+        Self {
+            steps: Vec::new(),
+            snapshots: vec![stacks.iter().map(PersistentStack::from_deque).collect()],
+            cursor: 0,
+        }
+    }
+
+    // how many steps have been applied to the current stacks
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    // records `command` as the next step, given the stacks after it ran;
+    // anything that had been undone can no longer be redone. A skipped
+    // command may name stacks that don't exist, so it leaves every stack as
+    // it was.
+    pub fn record(&mut self, command: &Command, stacks: &[VecDeque<String>], skipped: bool) {
+        self.steps.truncate(self.cursor);
+        self.snapshots.truncate(self.cursor + 1);
+
+        let previous = &self.snapshots[self.cursor];
+        let mut snapshot = previous.clone();
+        let moved = if skipped {
+            Vec::new()
+        } else {
+            for index in [command.source_index(), command.dest_index()] {
+                snapshot[index] = previous[index].advanced(&stacks[index]);
+            }
+            let dest_before = &previous[command.dest_index()];
+            let dest_after = &snapshot[command.dest_index()];
+            let num_arrived = dest_after.len.saturating_sub(dest_before.len);
+            dest_after.iter().take(num_arrived).cloned().collect()
+        };
+
+        self.steps.push(Step {
+            command: command.clone(),
+            moved,
+        });
+        self.snapshots.push(snapshot);
+        self.cursor += 1;
+    }
+
+    // moves the cursor to `step` and returns the snapshot there, as long as
+    // that step has been recorded
+    pub fn jump_to(&mut self, step: usize) -> Option<&Snapshot> {
+        if step > self.steps.len() {
+            return None;
+        }
+        self.cursor = step;
+        Some(&self.snapshots[step])
+    }
+
+    pub fn snapshot(&self, step: usize) -> Option<&Snapshot> {
+        self.snapshots.get(step)
+    }
+}
+
+// one line per stack, bottom crate first, e.g. "2: [M] [C] [D]"
+pub fn render(snapshot: &Snapshot) -> String {
+    snapshot
+        .iter()
+        .enumerate()
+        .map(|(i, stack)| {
+            let mut labels = stack
+                .iter()
+                .map(|label| format!("[{}]", label))
+                .collect::<Vec<_>>();
+            labels.reverse();
+            format!("{}: {}\n", i + 1, labels.join(" "))
+        })
+        .collect()
+}
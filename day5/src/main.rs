@@ -1,29 +1,244 @@
 use regex::Regex;
 use std::collections::VecDeque;
+use std::env;
+use std::error::Error;
+use std::fmt;
 use std::fs;
+use std::io::{self, BufRead};
 
-fn main() {
-    let mut tracker = StackTracker::new("resources/input_1");
-    tracker.execute_all();
-    let solution_1 = tracker.top_chars();
-    println!("Part 1 solution: {}", solution_1);
+use anyhow::{anyhow, Result};
 
-    let mut tracker2 = StackTracker::new("resources/input_1");
-    tracker2.execute_all_multi_crate_move();
-    let solution_2 = tracker2.top_chars();
-    println!("Part 2 solution: {}", solution_2);
+mod crane;
+mod history;
+mod planner;
+
+use crane::{crane_from_name, Crane, CrateMover9000, CrateMover9001};
+use history::History;
+use planner::{Goal, Planner};
+
+fn main() -> Result<()> {
+    let args = env::args().collect::<Vec<_>>();
+    match args.get(1).map(String::as_str) {
+        // e.g. `solve resources/example_1`
+        None | Some("solve") => {
+            let file_path = args
+                .get(2)
+                .map(String::as_str)
+                .unwrap_or("resources/input_1");
+
+            let mut tracker = StackTracker::new(file_path);
+            tracker.execute_all(&CrateMover9000, Mode::Strict)?;
+            let solution_1 = tracker.top_chars(Mode::Strict)?;
+            println!("Part 1 solution: {}", solution_1);
+
+            let mut tracker2 = StackTracker::new(file_path);
+            tracker2.execute_all(&CrateMover9001, Mode::Strict)?;
+            let solution_2 = tracker2.top_chars(Mode::Strict)?;
+            println!("Part 2 solution: {}", solution_2);
+        }
+        // e.g. `crane limited:3 resources/example_1 lenient`
+        Some("crane") => {
+            let crane = crane_from_name(args.get(2).expect("Expected a crane name"))?;
+            let file_path = args
+                .get(3)
+                .map(String::as_str)
+                .unwrap_or("resources/input_1");
+            let mode = match args.get(4).map(String::as_str) {
+                None | Some("strict") => Mode::Strict,
+                Some("lenient") => Mode::Lenient,
+                Some(other) => panic!("Unknown mode {}", other),
+            };
+
+            let mut tracker = StackTracker::new(file_path);
+            tracker.execute_all(crane.as_ref(), mode)?;
+            println!("{}: {:?}", crane.name(), tracker.top_chars(mode)?);
+        }
+        // e.g. `history 9001 resources/example_1`, then commands on stdin
+        Some("history") => {
+            let crane = crane_from_name(args.get(2).expect("Expected a crane name"))?;
+            let file_path = args
+                .get(3)
+                .map(String::as_str)
+                .unwrap_or("resources/input_1");
+            let mut tracker = StackTracker::new(file_path);
+            run_history_session(&mut tracker, crane.as_ref())?;
+        }
+        // e.g. `render 9001 resources/example_1 2 > resources/example_1_step_2`
+        Some("render") => {
+            let crane = crane_from_name(args.get(2).expect("Expected a crane name"))?;
+            let file_path = args
+                .get(3)
+                .map(String::as_str)
+                .unwrap_or("resources/input_1");
+            let mut tracker = StackTracker::new(file_path);
+            match args.get(4) {
+                Some(num_steps) => {
+                    for _ in 0..num_steps.parse::<usize>()? {
+                        tracker.step(crane.as_ref(), Mode::Strict)?;
+                    }
+                }
+                None => tracker.execute_all(crane.as_ref(), Mode::Strict)?,
+            }
+            print!("{}", tracker.to_input());
+        }
+        // e.g. `roundtrip resources/example_1`
+        Some("roundtrip") => {
+            let file_path = args
+                .get(2)
+                .map(String::as_str)
+                .unwrap_or("resources/input_1");
+            let file_content = fs::read_to_string(file_path)?;
+            let tracker = StackTracker::parse(&file_content);
+            let rendered = tracker.to_input();
+            let reparsed = StackTracker::parse(&rendered);
+            // line numbers shift whenever the drawing's height changes
+            let same_commands = tracker
+                .commands
+                .iter()
+                .map(Command::to_string)
+                .eq(reparsed.commands.iter().map(Command::to_string));
+            println!(
+                "Same stacks: {}, same commands: {}, identical text: {}",
+                tracker.stacks == reparsed.stacks,
+                same_commands,
+                rendered == file_content
+            );
+        }
+        // e.g. `plan 9001 resources/example_1 resources/example_1_target` or
+        // `plan 9000 resources/example_1 top:CMZ`, optionally with a node limit
+        Some("plan") => {
+            let crane = crane_from_name(args.get(2).expect("Expected a crane name"))?;
+            let start_path = args.get(3).expect("Expected a starting drawing");
+            let target = args
+                .get(4)
+                .expect("Expected a target drawing or top:CRATES");
+            let node_limit = match args.get(5) {
+                Some(limit) => limit.parse()?,
+                None => Planner::DEFAULT_NODE_LIMIT,
+            };
+
+            let mut tracker = StackTracker::new(start_path);
+            let goal = match target.strip_prefix("top:") {
+                Some(top_chars) => Goal::from_top_chars(top_chars),
+                None => Goal::Drawing(StackTracker::new(target).stacks),
+            };
+            match Planner::new(crane.as_ref(), goal, node_limit).plan(&tracker.stacks)? {
+                Some(plan) => {
+                    eprintln!(
+                        "Found a plan of {} moves for the {}",
+                        plan.len(),
+                        crane.name()
+                    );
+                    tracker.commands = plan;
+                    print!("{}", tracker.to_input());
+                }
+                None => eprintln!(
+                    "No plan found for the {} within {} nodes",
+                    crane.name(),
+                    node_limit
+                ),
+            }
+        }
+        Some(other) => panic!("Unknown subcommand {}", other),
+    }
+
+    Ok(())
+}
+
+// Reads one instruction per line: `next`, `run`, `undo`, `redo`, `goto N`,
+// `show [N]` or `log`. Commands are executed strictly. A bad instruction or
+// a command that fails is reported and the session carries on; only failing
+// to read stdin ends it.
+fn run_history_session(tracker: &mut StackTracker, crane: &dyn Crane) -> Result<()> {
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if let Err(error) = run_instruction(tracker, crane, &line) {
+            println!("{}", error);
+        }
+    }
+    Ok(())
+}
+
+fn run_instruction(tracker: &mut StackTracker, crane: &dyn Crane, line: &str) -> Result<()> {
+    let parse_step = |step: &str| {
+        step.parse::<usize>()
+            .map_err(|_| anyhow!("Expected a step number, not {:?}", step))
+    };
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    match words.as_slice() {
+        [] => (),
+        ["next"] => {
+            if !tracker.step(crane, Mode::Strict)? {
+                println!("No commands left");
+            }
+        }
+        ["run"] => while tracker.step(crane, Mode::Strict)? {},
+        ["undo"] => {
+            if !tracker.undo() {
+                println!("Nothing to undo");
+            }
+        }
+        ["redo"] => {
+            if !tracker.redo() {
+                println!("Nothing to redo");
+            }
+        }
+        ["goto", step] => {
+            if !tracker.jump_to(parse_step(step)?) {
+                println!("Step {} has not been recorded", step);
+            }
+        }
+        ["show"] => print!("{}", tracker.render(tracker.history.cursor()).unwrap()),
+        ["show", step] => match tracker.render(parse_step(step)?) {
+            Some(rendered) => print!("{}", rendered),
+            None => println!("Step {} has not been recorded", step),
+        },
+        ["log"] => {
+            for (i, step) in tracker.history.steps().iter().enumerate() {
+                let marker = if i < tracker.history.cursor() {
+                    ' '
+                } else {
+                    '*'
+                };
+                println!(
+                    "{}{:>4}  line {:>4}: move {} from {} to {}, moved {:?}",
+                    marker,
+                    i + 1,
+                    step.command.line_number,
+                    step.command.num_crates,
+                    step.command.source_stack_num,
+                    step.command.dest_stack_num,
+                    step.moved
+                );
+            }
+        }
+        _ => println!("Unknown instruction {:?}", line),
+    }
+    Ok(())
 }
 
 #[derive(Clone)]
 struct Command {
+    // one-based, as it appears in the input file
+    line_number: usize,
     num_crates: usize,
-    // indexes are zero-based
-    source_stack_index: usize,
-    dest_stack_index: usize,
+    // stack numbers are one-based, as they appear in the input file
+    source_stack_num: usize,
+    dest_stack_num: usize,
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.num_crates, self.source_stack_num, self.dest_stack_num
+        )
+    }
 }
 
 impl Command {
-    fn new(file_line: &str) -> Self {
+    fn new(file_line: &str, line_number: usize) -> Self {
         let pattern: Regex = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
         let cap = pattern.captures(file_line).unwrap();
         let num_crates = cap[1].parse::<usize>().unwrap();
@@ -31,95 +246,419 @@ impl Command {
         let dest_stack_num = cap[3].parse::<usize>().unwrap();
 
         Self {
+            line_number,
             num_crates,
-            source_stack_index: source_stack_num - 1,
-            dest_stack_index: dest_stack_num - 1,
+            source_stack_num,
+            dest_stack_num,
+        }
+    }
+
+    // only meaningful once the command has been checked against the stacks
+    fn source_index(&self) -> usize {
+        self.source_stack_num - 1
+    }
+
+    fn dest_index(&self) -> usize {
+        self.dest_stack_num - 1
+    }
+}
+
+// How to handle commands that don't fit the current stacks. Strict mode
+// stops at the first one with an error; lenient mode skips commands naming
+// a stack that doesn't exist, moves as many crates as it can, and reports
+// empty stacks as a space in the top crates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    Strict,
+    Lenient,
+}
+
+#[derive(Debug)]
+enum CommandError {
+    NoSuchStack {
+        line_number: usize,
+        stack_num: usize,
+        num_stacks: usize,
+    },
+    NotEnoughCrates {
+        line_number: usize,
+        num_crates: usize,
+        stack_num: usize,
+        stack_contents: Vec<String>,
+    },
+    EmptyStack {
+        stack_num: usize,
+    },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoSuchStack {
+                line_number,
+                stack_num,
+                num_stacks,
+            } => write!(
+                f,
+                "Line {}: there is no stack {}, only stacks 1 through {}",
+                line_number, stack_num, num_stacks
+            ),
+            Self::NotEnoughCrates {
+                line_number,
+                num_crates,
+                stack_num,
+                stack_contents,
+            } => write!(
+                f,
+                "Line {}: can't move {} crates from stack {}, which holds {:?} (top first)",
+                line_number, num_crates, stack_num, stack_contents
+            ),
+            Self::EmptyStack { stack_num } => write!(
+                f,
+                "Stack {} is empty after the last command, so it has no top crate",
+                stack_num
+            ),
         }
     }
 }
 
+impl Error for CommandError {}
+
 struct StackTracker {
-    stacks: Vec<VecDeque<char>>,
+    // the front of each stack is its top crate
+    stacks: Vec<VecDeque<String>>,
     commands: Vec<Command>,
+    // every command executed so far, with the stacks after each one;
+    // `history.cursor()` is also the index of the next command to execute
+    history: History,
 }
 
 impl StackTracker {
-    const NUM_STACKS: usize = 9;
-
     fn new(file_path: &str) -> Self {
-        let mut dividing_line = String::from("");
-        let mut stacks = Vec::with_capacity(Self::NUM_STACKS);
-        for n in 1..=Self::NUM_STACKS {
-            let end_chars = if n == Self::NUM_STACKS { "\n\n" } else { " " };
-            dividing_line.push_str(&format!(" {} {}", n, end_chars));
-            stacks.push(VecDeque::new());
+        Self::parse(&fs::read_to_string(file_path).unwrap())
+    }
+
+    // parses a puzzle input: the crate drawing, a blank line, then commands
+    fn parse(file_content: &str) -> Self {
+        let mut lines = file_content.lines().enumerate();
+        let drawing = lines
+            .by_ref()
+            .map(|(_, line)| line)
+            .take_while(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+        let (footer, crate_rows) = drawing
+            .split_last()
+            .expect("Could not find a crate drawing");
+
+        let stack_columns = Self::parse_footer(footer);
+        let mut stacks = vec![VecDeque::new(); stack_columns.len()];
+        for crate_row in crate_rows {
+            for (column, label) in Self::parse_crate_row(crate_row) {
+                stacks[Self::nearest_stack(&stack_columns, column)].push_back(label);
+            }
         }
 
-        let file_content = fs::read_to_string(file_path).unwrap();
-        let mut split_file = file_content.split(&dividing_line);
-        let (unparsed_crates, unparsed_commands) =
-            (split_file.next().unwrap(), split_file.next().unwrap());
-
-        for unparsed_crate_row in unparsed_crates.lines() {
-            for (i, char) in unparsed_crate_row.chars().enumerate() {
-                if char != ' ' && char != '[' && char != ']' {
-                    let stack_index = i / 4; // integer division
-                    stacks[stack_index].push_back(char);
+        let mut commands = Vec::new();
+        for (i, unparsed_command) in lines.filter(|(_, line)| !line.trim().is_empty()) {
+            commands.push(Command::new(unparsed_command, i + 1));
+        }
+
+        let history = History::new(&stacks);
+        Self {
+            stacks,
+            commands,
+            history,
+        }
+    }
+
+    // The footer numbers every stack, e.g. " 1   2   3 ". Returns the column
+    // each stack's number is centered on, indexed by stack.
+    fn parse_footer(footer: &str) -> Vec<f64> {
+        let chars = footer.chars().collect::<Vec<_>>();
+        let mut stack_columns = Vec::new();
+        let mut number_start = None;
+        // a trailing space makes sure the last number gets closed off
+        for (i, &char) in chars.iter().chain([' '].iter()).enumerate() {
+            match (char.is_ascii_digit(), number_start) {
+                (true, None) => number_start = Some(i),
+                (false, Some(start)) => {
+                    let stack_num = chars[start..i]
+                        .iter()
+                        .collect::<String>()
+                        .parse::<usize>()
+                        .unwrap();
+                    if stack_num != stack_columns.len() + 1 {
+                        panic!(
+                            "Expected stack {} in footer, found {}",
+                            stack_columns.len() + 1,
+                            stack_num
+                        );
+                    }
+                    stack_columns.push((start + i - 1) as f64 / 2.0);
+                    number_start = None;
                 }
+                (false, None) if !char.is_whitespace() => {
+                    panic!("Could not find numbered footer line, found {:?}", footer)
+                }
+                _ => (),
             }
         }
 
-        let mut commands = Vec::new();
-        for unparsed_command in unparsed_commands.lines() {
-            commands.push(Command::new(unparsed_command));
+        if stack_columns.is_empty() {
+            panic!("Could not find numbered footer line, found {:?}", footer);
         }
+        stack_columns
+    }
 
-        Self { stacks, commands }
+    // Finds every bracketed crate in a row of the drawing, like "[Z] [MM]",
+    // returning the column its label is centered on along with the label.
+    fn parse_crate_row(crate_row: &str) -> Vec<(f64, String)> {
+        let mut crates = Vec::new();
+        let mut label_start = None;
+        for (i, char) in crate_row.chars().enumerate() {
+            match (char, label_start) {
+                ('[', None) => label_start = Some(i),
+                (']', Some(start)) => {
+                    let label = crate_row
+                        .chars()
+                        .skip(start + 1)
+                        .take(i - start - 1)
+                        .collect();
+                    crates.push(((start + i) as f64 / 2.0, label));
+                    label_start = None;
+                }
+                _ => (),
+            }
+        }
+        crates
     }
 
-    fn execute_all(&mut self) {
-        let commands = self.commands.clone();
-        commands
-            .iter()
-            .cloned()
-            .for_each(|command| self.execute(command))
+    fn nearest_stack(stack_columns: &[f64], column: f64) -> usize {
+        (0..stack_columns.len())
+            .min_by(|&a, &b| {
+                let distance_a = (stack_columns[a] - column).abs();
+                let distance_b = (stack_columns[b] - column).abs();
+                distance_a.total_cmp(&distance_b)
+            })
+            .unwrap()
     }
 
-    fn execute_all_multi_crate_move(&mut self) {
-        let commands = self.commands.clone();
-        commands
-            .iter()
-            .cloned()
-            .for_each(|command| self.execute_multi_crate_move(command))
+    fn execute_all(&mut self, crane: &dyn Crane, mode: Mode) -> Result<(), CommandError> {
+        while self.step(crane, mode)? {}
+        Ok(())
     }
 
-    fn execute(&mut self, command: Command) {
-        for _ in 0..command.num_crates {
-            if let Some(removed_crate) = self.stacks[command.source_stack_index].pop_front() {
-                self.stacks[command.dest_stack_index].push_front(removed_crate);
+    // Executes the next command and records it in the history, discarding
+    // any undone steps. Returns false once there are no commands left.
+    // Commands skipped in lenient mode are still recorded, moving nothing.
+    fn step(&mut self, crane: &dyn Crane, mode: Mode) -> Result<bool, CommandError> {
+        let Some(command) = self.commands.get(self.history.cursor()) else {
+            return Ok(false);
+        };
+        let skipped = match Self::check(&self.stacks, command) {
+            Ok(()) => false,
+            Err(CommandError::NotEnoughCrates { .. }) if mode == Mode::Lenient => false,
+            Err(_) if mode == Mode::Lenient => true,
+            Err(error) => return Err(error),
+        };
+        if !skipped {
+            crane.execute(&mut self.stacks, command);
+        }
+        self.history.record(command, &self.stacks, skipped);
+        Ok(true)
+    }
+
+    fn undo(&mut self) -> bool {
+        self.history.cursor() > 0 && self.jump_to(self.history.cursor() - 1)
+    }
+
+    fn redo(&mut self) -> bool {
+        self.jump_to(self.history.cursor() + 1)
+    }
+
+    // restores the stacks as they were after `step` commands, as long as that
+    // step has been recorded
+    fn jump_to(&mut self, step: usize) -> bool {
+        match self.history.jump_to(step) {
+            Some(snapshot) => {
+                self.stacks = snapshot.iter().map(|stack| stack.to_deque()).collect();
+                true
             }
+            None => false,
         }
     }
 
-    fn execute_multi_crate_move(&mut self, command: Command) {
-        let mut intermediate = VecDeque::new();
-        for _ in 0..command.num_crates {
-            if let Some(removed_crate) = self.stacks[command.source_stack_index].pop_front() {
-                intermediate.push_front(removed_crate);
+    fn render(&self, step: usize) -> Option<String> {
+        self.history.snapshot(step).map(history::render)
+    }
+
+    // The current stacks drawn the way the puzzle draws them. Each column is
+    // as wide as its widest crate or stack number, with one space between
+    // columns, and everything is centered within its column. Crate rows have
+    // trailing spaces trimmed; the footer keeps them.
+    fn drawing(&self) -> String {
+        let stack_nums = (1..=self.stacks.len())
+            .map(|stack_num| stack_num.to_string())
+            .collect::<Vec<_>>();
+        let widths = self
+            .stacks
+            .iter()
+            .zip(stack_nums.iter())
+            .map(|(stack, stack_num)| {
+                let widest_crate = stack
+                    .iter()
+                    .map(|label| label.chars().count() + 2)
+                    .max()
+                    .unwrap_or(0);
+                widest_crate.max(stack_num.len() + 2)
+            })
+            .collect::<Vec<_>>();
+        let height = self.stacks.iter().map(VecDeque::len).max().unwrap_or(0);
+
+        let mut drawing = String::new();
+        for row in 0..height {
+            let cells = self
+                .stacks
+                .iter()
+                .zip(widths.iter())
+                .map(|(stack, &width)| {
+                    // row 0 is the top of the drawing, so short stacks are
+                    // padded from above
+                    match (row + stack.len()).checked_sub(height) {
+                        Some(depth) => Self::centered(&format!("[{}]", stack[depth]), width),
+                        None => " ".repeat(width),
+                    }
+                })
+                .collect::<Vec<_>>();
+            drawing.push_str(cells.join(" ").trim_end());
+            drawing.push('\n');
+        }
+        let footer = stack_nums
+            .iter()
+            .zip(widths.iter())
+            .map(|(stack_num, &width)| Self::centered(stack_num, width))
+            .collect::<Vec<_>>();
+        drawing.push_str(&footer.join(" "));
+        drawing.push('\n');
+        drawing
+    }
+
+    fn centered(text: &str, width: usize) -> String {
+        let padding = width - text.chars().count();
+        format!(
+            "{}{}{}",
+            " ".repeat(padding / 2),
+            text,
+            " ".repeat(padding - padding / 2)
+        )
+    }
+
+    // A complete puzzle input for the rest of the rearrangement: the current
+    // stacks, then every command that hasn't been executed yet. Parsing it
+    // gives back an equivalent tracker at step 0.
+    fn to_input(&self) -> String {
+        let mut input = self.drawing();
+        input.push('\n');
+        for command in &self.commands[self.history.cursor()..] {
+            input.push_str(&format!("{}\n", command));
+        }
+        input
+    }
+
+    // checks that a command can be carried out in full against `stacks`
+    fn check(stacks: &[VecDeque<String>], command: &Command) -> Result<(), CommandError> {
+        for stack_num in [command.source_stack_num, command.dest_stack_num] {
+            if stack_num == 0 || stack_num > stacks.len() {
+                return Err(CommandError::NoSuchStack {
+                    line_number: command.line_number,
+                    stack_num,
+                    num_stacks: stacks.len(),
+                });
             }
         }
-        for moved_crate in intermediate.into_iter() {
-            self.stacks[command.dest_stack_index].push_front(moved_crate);
+
+        let source = &stacks[command.source_index()];
+        if command.num_crates > source.len() {
+            return Err(CommandError::NotEnoughCrates {
+                line_number: command.line_number,
+                num_crates: command.num_crates,
+                stack_num: command.source_stack_num,
+                stack_contents: source.iter().cloned().collect(),
+            });
         }
+
+        Ok(())
     }
 
-    fn top_chars(&self) -> String {
+    fn top_chars(&self, mode: Mode) -> Result<String, CommandError> {
         self.stacks
             .iter()
-            .map(|stack| stack.front().unwrap())
-            .collect::<String>()
+            .enumerate()
+            .map(|(i, stack)| match (stack.front(), mode) {
+                (Some(top_crate), _) => Ok(top_crate.as_str()),
+                (None, Mode::Lenient) => Ok(" "),
+                (None, Mode::Strict) => Err(CommandError::EmptyStack { stack_num: i + 1 }),
+            })
+            .collect()
     }
 }
 
 // find away not to rebuild regex on every call of Command::new
-// any way to avoid cloning commands?
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // parsing a drawing rendered by `to_input` gives back the same stacks and
+    // commands; line numbers aren't compared, since they shift whenever the
+    // drawing's height changes
+    fn assert_roundtrip(file_path: &str) -> String {
+        let file_content = fs::read_to_string(file_path).unwrap();
+        let tracker = StackTracker::parse(&file_content);
+        let rendered = tracker.to_input();
+        let reparsed = StackTracker::parse(&rendered);
+        assert_eq!(
+            tracker.stacks, reparsed.stacks,
+            "{}: stacks differ",
+            file_path
+        );
+        assert_eq!(
+            tracker
+                .commands
+                .iter()
+                .map(Command::to_string)
+                .collect::<Vec<_>>(),
+            reparsed
+                .commands
+                .iter()
+                .map(Command::to_string)
+                .collect::<Vec<_>>(),
+            "{}: commands differ",
+            file_path
+        );
+        rendered
+    }
+
+    #[test]
+    fn example_roundtrips() {
+        assert_roundtrip("resources/example_1");
+    }
+
+    #[test]
+    fn input_roundtrips_to_identical_text() {
+        let rendered = assert_roundtrip("resources/input_1");
+        assert_eq!(rendered, fs::read_to_string("resources/input_1").unwrap());
+    }
+
+    #[test]
+    fn lenient_skip_records_nothing_moved() {
+        let file_content = "[Z] [M]\n 1   2 \n\nmove 1 from 0 to 1\nmove 1 from 1 to 3\n";
+        let mut tracker = StackTracker::parse(file_content);
+        tracker.execute_all(&CrateMover9000, Mode::Lenient).unwrap();
+
+        let steps = tracker.history.steps();
+        assert_eq!(steps.len(), 2);
+        assert!(steps.iter().all(|step| step.moved.is_empty()));
+        assert_eq!(tracker.top_chars(Mode::Lenient).unwrap(), "ZM");
+    }
+}
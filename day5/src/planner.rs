@@ -0,0 +1,287 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+use crate::crane::Crane;
+use crate::Command;
+
+// What the rearrangement should end with
+pub enum Goal {
+    // every stack, top crate first, exactly as drawn
+    Drawing(Vec<VecDeque<String>>),
+    // just the top crate of each stack, `None` for a stack that ends up empty
+    TopCrates(Vec<Option<String>>),
+}
+
+impl Goal {
+    // reads a top-crates string like "CMZ", one character per stack, where a
+    // space stands for an empty stack
+    pub fn from_top_chars(top_chars: &str) -> Self {
+        Self::TopCrates(
+            top_chars
+                .chars()
+                .map(|top_char| match top_char {
+                    ' ' => None,
+                    _ => Some(top_char.to_string()),
+                })
+                .collect(),
+        )
+    }
+
+    fn num_stacks(&self) -> usize {
+        match self {
+            Self::Drawing(stacks) => stacks.len(),
+            Self::TopCrates(tops) => tops.len(),
+        }
+    }
+
+    fn is_reached(&self, stacks: &[VecDeque<String>]) -> bool {
+        match self {
+            Self::Drawing(target) => stacks == target.as_slice(),
+            Self::TopCrates(tops) => stacks
+                .iter()
+                .zip(tops.iter())
+                .all(|(stack, top)| stack.front() == top.as_ref()),
+        }
+    }
+
+    // A lower bound on the number of moves still needed. Every move has one
+    // source and one destination stack. For a full drawing, a stack with a
+    // wrong crate anywhere must be a source at least once, to clear
+    // everything from that crate up, and a stack still missing crates must
+    // be a destination at least once. For top crates alone, a move changes
+    // at most two tops, so each wrong top needs half a move.
+    fn estimate(&self, stacks: &[VecDeque<String>]) -> usize {
+        match self {
+            Self::Drawing(target) => {
+                let mut sources_needed = 0;
+                let mut dests_needed = 0;
+                for (stack, target_stack) in stacks.iter().zip(target.iter()) {
+                    let settled = stack
+                        .iter()
+                        .rev()
+                        .zip(target_stack.iter().rev())
+                        .take_while(|(label, target_label)| label == target_label)
+                        .count();
+                    if stack.len() > settled {
+                        sources_needed += 1;
+                    }
+                    if target_stack.len() > settled {
+                        dests_needed += 1;
+                    }
+                }
+                sources_needed.max(dests_needed)
+            }
+            Self::TopCrates(tops) => {
+                let wrong_tops = stacks
+                    .iter()
+                    .zip(tops.iter())
+                    .filter(|(stack, top)| stack.front() != top.as_ref())
+                    .count();
+                wrong_tops.div_ceil(2)
+            }
+        }
+    }
+
+    // checks that the goal could be reached from `stacks` by some sequence
+    // of moves, ignoring what the crane can do
+    fn check(&self, stacks: &[VecDeque<String>]) -> Result<(), PlanError> {
+        if self.num_stacks() != stacks.len() {
+            return Err(PlanError::StackCountMismatch {
+                num_stacks: stacks.len(),
+                num_target_stacks: self.num_stacks(),
+            });
+        }
+
+        let mut available = HashMap::new();
+        for label in stacks.iter().flatten() {
+            *available.entry(label.as_str()).or_insert(0) += 1;
+        }
+        let mut needed = HashMap::new();
+        match self {
+            Self::Drawing(target) => {
+                for label in target.iter().flatten() {
+                    *needed.entry(label.as_str()).or_insert(0) += 1;
+                }
+                if needed != available {
+                    return Err(PlanError::DifferentCrates);
+                }
+            }
+            Self::TopCrates(tops) => {
+                for label in tops.iter().flatten() {
+                    *needed.entry(label.as_str()).or_insert(0) += 1;
+                }
+                for (label, &count) in needed.iter() {
+                    if available.get(label).copied().unwrap_or(0) < count {
+                        return Err(PlanError::MissingCrate {
+                            label: label.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum PlanError {
+    StackCountMismatch {
+        num_stacks: usize,
+        num_target_stacks: usize,
+    },
+    // the target drawing doesn't hold exactly the crates the start does
+    DifferentCrates,
+    // the target wants a crate on top that the stacks don't hold (enough of)
+    MissingCrate {
+        label: String,
+    },
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::StackCountMismatch {
+                num_stacks,
+                num_target_stacks,
+            } => write!(
+                f,
+                "The start has {} stacks, but the target has {}",
+                num_stacks, num_target_stacks
+            ),
+            Self::DifferentCrates => write!(
+                f,
+                "The target drawing doesn't hold the same crates as the start"
+            ),
+            Self::MissingCrate { label } => write!(
+                f,
+                "The target wants {:?} on top more often than the stacks hold it",
+                label
+            ),
+        }
+    }
+}
+
+impl Error for PlanError {}
+
+// Searches for the shortest list of commands that takes the stacks to the
+// goal, using iterative deepening A*: a depth-first search that gives up on
+// any path whose length plus `Goal::estimate` exceeds a bound, raising the
+// bound to the smallest overshoot after each unsuccessful pass. Because the
+// estimate never overshoots, the first plan found is a shortest one, while
+// memory use stays proportional to the plan's length. States already on the
+// current path are skipped, since revisiting one can never shorten a plan.
+pub struct Planner<'a> {
+    crane: &'a dyn Crane,
+    goal: Goal,
+    node_limit: usize,
+}
+
+struct SearchState {
+    stacks: Vec<VecDeque<String>>,
+    path: Vec<Command>,
+    on_path: HashSet<Vec<VecDeque<String>>>,
+    nodes_visited: usize,
+}
+
+// how a pass of the search ended
+enum Pass {
+    Found,
+    // the smallest path length plus estimate that went over the bound, if any
+    Exceeded(Option<usize>),
+    OutOfNodes,
+}
+
+impl<'a> Planner<'a> {
+    pub const DEFAULT_NODE_LIMIT: usize = 1_000_000;
+
+    pub fn new(crane: &'a dyn Crane, goal: Goal, node_limit: usize) -> Self {
+        Self {
+            crane,
+            goal,
+            node_limit,
+        }
+    }
+
+    // `Ok(None)` means the goal can't be reached with this crane, or that no
+    // plan was found before the node limit was reached
+    pub fn plan(&self, stacks: &[VecDeque<String>]) -> Result<Option<Vec<Command>>, PlanError> {
+        self.goal.check(stacks)?;
+
+        let mut state = SearchState {
+            stacks: stacks.to_vec(),
+            path: Vec::new(),
+            on_path: HashSet::from([stacks.to_vec()]),
+            nodes_visited: 0,
+        };
+        let mut bound = self.goal.estimate(stacks);
+        loop {
+            match self.search(&mut state, bound) {
+                Pass::Found => return Ok(Some(state.path)),
+                Pass::Exceeded(Some(next_bound)) => bound = next_bound,
+                Pass::Exceeded(None) | Pass::OutOfNodes => return Ok(None),
+            }
+        }
+    }
+
+    fn search(&self, state: &mut SearchState, bound: usize) -> Pass {
+        let total = state.path.len() + self.goal.estimate(&state.stacks);
+        if total > bound {
+            return Pass::Exceeded(Some(total));
+        }
+        if self.goal.is_reached(&state.stacks) {
+            return Pass::Found;
+        }
+
+        let mut next_bound: Option<usize> = None;
+        for command in self.moves(&state.stacks, state.path.len() + 1) {
+            if state.nodes_visited >= self.node_limit {
+                return Pass::OutOfNodes;
+            }
+            state.nodes_visited += 1;
+
+            let mut next_stacks = state.stacks.clone();
+            self.crane.execute(&mut next_stacks, &command);
+            if !state.on_path.insert(next_stacks.clone()) {
+                continue;
+            }
+            let previous_stacks = std::mem::replace(&mut state.stacks, next_stacks);
+            state.path.push(command);
+
+            let pass = self.search(state, bound);
+            if let Pass::Found | Pass::OutOfNodes = pass {
+                return pass;
+            }
+
+            state.path.pop();
+            let next_stacks = std::mem::replace(&mut state.stacks, previous_stacks);
+            state.on_path.remove(&next_stacks);
+            if let Pass::Exceeded(Some(total)) = pass {
+                next_bound = Some(next_bound.map_or(total, |bound| bound.min(total)));
+            }
+        }
+        Pass::Exceeded(next_bound)
+    }
+
+    // every command that can be carried out in full, numbered as line
+    // `line_number` of the plan
+    fn moves(&self, stacks: &[VecDeque<String>], line_number: usize) -> Vec<Command> {
+        let mut moves = Vec::new();
+        for source_index in 0..stacks.len() {
+            for dest_index in 0..stacks.len() {
+                if source_index == dest_index {
+                    continue;
+                }
+                for num_crates in 1..=stacks[source_index].len() {
+                    moves.push(Command {
+                        line_number,
+                        num_crates,
+                        source_stack_num: source_index + 1,
+                        dest_stack_num: dest_index + 1,
+                    });
+                }
+            }
+        }
+        moves
+    }
+}
@@ -0,0 +1,221 @@
+use std::fmt;
+use std::io::{self, BufReader, Bytes, Read};
+
+use crate::marker::MarkerWindow;
+
+// Where one frame ends and the next begins, once the stream's first marker
+// has been found
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Boundary {
+    // every frame is followed by a fresh marker, which isn't part of it
+    NextMarker,
+    // every frame starts with its payload length as a big-endian integer
+    // `width` bytes wide
+    LengthPrefix { width: usize },
+    // frames are separated by this byte, which isn't part of either frame
+    Delimiter(u8),
+}
+
+impl Boundary {
+    // parses "marker", "length:N" or "delimiter:C", where C is a single
+    // character or `\n`
+    pub fn from_name(name: &str) -> Self {
+        match name.split_once(':') {
+            None if name == "marker" => Self::NextMarker,
+            Some(("length", width)) => {
+                let width = width
+                    .parse()
+                    .expect("Length prefix width must be an integer");
+                if !(1..=8).contains(&width) {
+                    panic!("Length prefix must be 1 to 8 bytes wide, not {}", width);
+                }
+                Self::LengthPrefix { width }
+            }
+            Some(("delimiter", "\\n")) => Self::Delimiter(b'\n'),
+            Some(("delimiter", delimiter)) if delimiter.len() == 1 => {
+                Self::Delimiter(delimiter.as_bytes()[0])
+            }
+            _ => panic!("Unknown frame boundary {}", name),
+        }
+    }
+}
+
+impl fmt::Display for Boundary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NextMarker => write!(f, "marker"),
+            Self::LengthPrefix { width } => write!(f, "length:{}", width),
+            Self::Delimiter(b'\n') => write!(f, "delimiter:\\n"),
+            Self::Delimiter(delimiter) => write!(f, "delimiter:{}", *delimiter as char),
+        }
+    }
+}
+
+// Splits a datastream into frames, starting right after its first marker of
+// `window` distinct bytes; anything before that is noise. Yields each frame
+// as the zero-based offset of its payload in the stream, and the payload.
+// A frame cut short by the end of the stream is an error for length
+// prefixed frames, and is yielded as it is otherwise.
+pub struct FrameDecoder<R: Read> {
+    bytes: Bytes<BufReader<R>>,
+    window: usize,
+    boundary: Boundary,
+    // how many bytes have been read so far
+    position: usize,
+    started: bool,
+    finished: bool,
+}
+
+impl<R: Read> FrameDecoder<R> {
+    pub fn new(reader: R, window: usize, boundary: Boundary) -> Self {
+        Self {
+            bytes: BufReader::new(reader).bytes(),
+            window,
+            boundary,
+            position: 0,
+            started: false,
+            finished: false,
+        }
+    }
+
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        let byte = self.bytes.next().transpose()?;
+        if byte.is_some() {
+            self.position += 1;
+        }
+        Ok(byte)
+    }
+
+    // reads up to and including the first marker, returning false if the
+    // stream ends first
+    fn skip_past_marker(&mut self) -> io::Result<bool> {
+        let mut marker_window = MarkerWindow::new(self.window);
+        while let Some(byte) = self.next_byte()? {
+            if marker_window.push(byte) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn next_frame(&mut self) -> io::Result<Option<(usize, Vec<u8>)>> {
+        if !self.started {
+            self.started = true;
+            if !self.skip_past_marker()? {
+                return Ok(None);
+            }
+        }
+
+        let offset = self.position;
+        let mut payload = Vec::new();
+        match self.boundary {
+            Boundary::NextMarker => {
+                let mut marker_window = MarkerWindow::new(self.window);
+                while let Some(byte) = self.next_byte()? {
+                    payload.push(byte);
+                    if marker_window.push(byte) {
+                        payload.truncate(payload.len() - self.window);
+                        return Ok(Some((offset, payload)));
+                    }
+                }
+            }
+            Boundary::LengthPrefix { width } => {
+                let mut len = 0;
+                for i in 0..width {
+                    match self.next_byte()? {
+                        Some(byte) => len = (len << 8) | byte as usize,
+                        None if i == 0 => return Ok(None),
+                        None => return Err(Self::truncated(offset)),
+                    }
+                }
+                let offset = self.position;
+                for _ in 0..len {
+                    match self.next_byte()? {
+                        Some(byte) => payload.push(byte),
+                        None => return Err(Self::truncated(offset)),
+                    }
+                }
+                return Ok(Some((offset, payload)));
+            }
+            Boundary::Delimiter(delimiter) => {
+                while let Some(byte) = self.next_byte()? {
+                    if byte == delimiter {
+                        return Ok(Some((offset, payload)));
+                    }
+                    payload.push(byte);
+                }
+            }
+        }
+
+        // the stream ended partway through a frame
+        if payload.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some((offset, payload)))
+        }
+    }
+
+    fn truncated(offset: usize) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!(
+                "Stream ended partway through the frame at offset {}",
+                offset
+            ),
+        )
+    }
+}
+
+impl<R: Read> Iterator for FrameDecoder<R> {
+    type Item = io::Result<(usize, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let frame = self.next_frame().transpose();
+        if !matches!(frame, Some(Ok(_))) {
+            self.finished = true;
+        }
+        frame
+    }
+}
+
+// every frame the decoder yields, with its payload shown as (lossy) text
+pub fn to_json<R: Read>(decoder: FrameDecoder<R>) -> io::Result<String> {
+    let window = decoder.window;
+    let boundary = decoder.boundary;
+    let mut frames = Vec::new();
+    for frame in decoder {
+        let (offset, payload) = frame?;
+        frames.push(format!(
+            "    {{\"offset\":{},\"len\":{},\"payload\":\"{}\"}}",
+            offset,
+            payload.len(),
+            escape_json(&String::from_utf8_lossy(&payload))
+        ));
+    }
+
+    Ok(format!(
+        "{{\n  \"marker_window\": {},\n  \"boundary\": \"{}\",\n  \"frames\": [\n{}\n  ]\n}}\n",
+        window,
+        escape_json(&boundary.to_string()),
+        frames.join(",\n")
+    ))
+}
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::new();
+    for char in text.chars() {
+        match char {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            char if char.is_control() => escaped.push_str(&format!("\\u{:04x}", char as u32)),
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
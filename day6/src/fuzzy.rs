@@ -0,0 +1,133 @@
+use std::fmt;
+use std::io::{self, BufReader, Read};
+
+use crate::marker::MarkerWindow;
+
+// How far a window may stray from being all distinct and still count as a
+// marker. For a full window of width `w`, at most `k` duplicates is the
+// same as at least `w - k` distinct bytes; both are accepted so thresholds
+// can be given whichever way is more natural.
+#[derive(Clone, Copy, Debug)]
+pub enum Tolerance {
+    MaxDuplicates(usize),
+    MinDistinct(usize),
+}
+
+impl Tolerance {
+    // parses "dups:K" or "distinct:D"
+    pub fn from_name(name: &str) -> Self {
+        match name.split_once(':') {
+            Some(("dups", max)) => {
+                Self::MaxDuplicates(max.parse().expect("Max duplicates must be an integer"))
+            }
+            Some(("distinct", min)) => {
+                Self::MinDistinct(min.parse().expect("Min distinct must be an integer"))
+            }
+            _ => panic!("Unknown tolerance {}", name),
+        }
+    }
+
+    fn accepts(&self, marker_window: &MarkerWindow) -> bool {
+        marker_window.is_full()
+            && match *self {
+                Self::MaxDuplicates(max) => marker_window.duplicates() <= max,
+                Self::MinDistinct(min) => marker_window.distinct() >= min,
+            }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Candidate {
+    // one-based position of the window's last byte, as `find_marker` reports
+    pub end: usize,
+    pub distinct: usize,
+    // the share of the window that's distinct, so 1.0 is an exact marker
+    pub confidence: f64,
+}
+
+impl fmt::Display for Candidate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ends at {}, {} distinct, confidence {:.3}",
+            self.end, self.distinct, self.confidence
+        )
+    }
+}
+
+pub struct FuzzyMarker {
+    pub window: usize,
+    pub tolerance: Tolerance,
+    // the first window the tolerance accepts
+    pub found: Option<Candidate>,
+    // every accepted window, the most confident first and the earliest among
+    // equals, cut off after the requested number
+    pub candidates: Vec<Candidate>,
+    pub num_candidates: usize,
+}
+
+impl fmt::Display for FuzzyMarker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Window {}, tolerance {:?}", self.window, self.tolerance)?;
+        match self.found {
+            Some(found) => writeln!(f, "Found: {}", found)?,
+            None => writeln!(f, "Found: nothing")?,
+        }
+        writeln!(
+            f,
+            "{} candidate windows, best {}:",
+            self.num_candidates,
+            self.candidates.len()
+        )?;
+        for candidate in self.candidates.iter() {
+            writeln!(f, "  {}", candidate)?;
+        }
+        Ok(())
+    }
+}
+
+// Scans the whole stream for windows the tolerance accepts, keeping the
+// first one and the `max_candidates` most confident. Every window is
+// checked in O(1) from the running duplicate count.
+pub fn find_fuzzy_marker<R: Read>(
+    reader: R,
+    window: usize,
+    tolerance: Tolerance,
+    max_candidates: usize,
+) -> io::Result<FuzzyMarker> {
+    let mut marker_window = MarkerWindow::new(window);
+    let mut result = FuzzyMarker {
+        window,
+        tolerance,
+        found: None,
+        candidates: Vec::new(),
+        num_candidates: 0,
+    };
+    for byte in BufReader::new(reader).bytes() {
+        marker_window.push(byte?);
+        if !tolerance.accepts(&marker_window) {
+            continue;
+        }
+
+        let candidate = Candidate {
+            end: marker_window.position(),
+            distinct: marker_window.distinct(),
+            confidence: marker_window.distinct() as f64 / window as f64,
+        };
+        result.found.get_or_insert(candidate);
+        result.num_candidates += 1;
+
+        // candidates arrive in order, so a later one only beats an earlier
+        // one with strictly more distinct bytes
+        let rank = result
+            .candidates
+            .iter()
+            .position(|other| other.distinct < candidate.distinct)
+            .unwrap_or(result.candidates.len());
+        if rank < max_candidates {
+            result.candidates.insert(rank, candidate);
+            result.candidates.truncate(max_candidates);
+        }
+    }
+    Ok(result)
+}
@@ -1,64 +1,134 @@
-use std::collections::{HashSet, VecDeque};
-use std::fs;
+use std::env;
+use std::fs::File;
+use std::io;
 
-fn main() {
-    let analyzer = StreamAnalyzer::new("resources/input_1");
-    let solution_1 = analyzer.count_til_end_marker().unwrap();
-    println!("Part 1 solution: {}", solution_1);
+mod framing;
+mod fuzzy;
+mod marker;
+mod runs;
 
-    let solution_2 = analyzer.count_til_start_marker().unwrap();
-    println!("Part 2 solution: {}", solution_2);
+use framing::{Boundary, FrameDecoder};
+use fuzzy::{FuzzyMarker, Tolerance};
+use runs::StreamStats;
+
+fn main() -> io::Result<()> {
+    let args = env::args().collect::<Vec<_>>();
+    match args.get(1).map(String::as_str) {
+        // e.g. `solve resources/input_1`
+        None | Some("solve") => {
+            let analyzer =
+                StreamAnalyzer::new(args.get(2).map_or("resources/input_1", String::as_str));
+            let solution_1 = analyzer
+                .find_marker(StreamAnalyzer::PACKET_MARKER_LEN)?
+                .unwrap();
+            println!("Part 1 solution: {}", solution_1);
+
+            let solution_2 = analyzer
+                .find_marker(StreamAnalyzer::MESSAGE_MARKER_LEN)?
+                .unwrap();
+            println!("Part 2 solution: {}", solution_2);
+        }
+        // e.g. `frames message delimiter:, resources/input_1`
+        Some("frames") => {
+            let window = StreamAnalyzer::window_from_name(args.get(2).expect("Expected a marker"));
+            let boundary = Boundary::from_name(args.get(3).expect("Expected a frame boundary"));
+            let analyzer =
+                StreamAnalyzer::new(args.get(4).map_or("resources/input_1", String::as_str));
+            print!("{}", framing::to_json(analyzer.frames(window, boundary)?)?);
+        }
+        // e.g. `stats packet,message,20 resources/input_1`, one datastream per line
+        Some("stats") => {
+            let windows = args
+                .get(2)
+                .expect("Expected comma-separated markers")
+                .split(',')
+                .map(StreamAnalyzer::window_from_name)
+                .collect::<Vec<_>>();
+            let analyzer =
+                StreamAnalyzer::new(args.get(3).map_or("resources/input_1", String::as_str));
+            for stats in analyzer.stats(&windows)? {
+                print!("{}", stats);
+            }
+        }
+        // e.g. `fuzzy message dups:1 resources/input_1 5`
+        Some("fuzzy") => {
+            let window = StreamAnalyzer::window_from_name(args.get(2).expect("Expected a marker"));
+            let tolerance = Tolerance::from_name(args.get(3).expect("Expected a tolerance"));
+            let analyzer =
+                StreamAnalyzer::new(args.get(4).map_or("resources/input_1", String::as_str));
+            let max_candidates = args
+                .get(5)
+                .map_or(StreamAnalyzer::DEFAULT_CANDIDATES, |max| {
+                    max.parse().expect("Candidate count must be an integer")
+                });
+            print!(
+                "{}",
+                analyzer.find_fuzzy_marker(window, tolerance, max_candidates)?
+            );
+        }
+        Some(other) => panic!("Unknown subcommand {}", other),
+    }
+
+    Ok(())
 }
 
+// Analyzes a datastream file by streaming it from disk on every query, so
+// captures much larger than memory can be scanned.
 struct StreamAnalyzer {
-    stream: String,
+    file_path: String,
 }
 
 impl StreamAnalyzer {
+    const PACKET_MARKER_LEN: usize = 4;
+    const MESSAGE_MARKER_LEN: usize = 14;
+    const DEFAULT_CANDIDATES: usize = 10;
+
     fn new(file_path: &str) -> Self {
-        let file_content = fs::read_to_string(file_path).unwrap();
         Self {
-            stream: file_content,
+            file_path: String::from(file_path),
         }
     }
 
-    fn count_til_end_marker(&self) -> Option<usize> {
-        let chars = self.stream.chars();
-        let mut last_four = VecDeque::new();
-
-        for (i, char) in chars.enumerate() {
-            last_four.push_front(char);
-            if (last_four.len() == 5) {
-                last_four.pop_back();
-            }
-
-            let mut unique_last_four: HashSet<char> = HashSet::new();
-            unique_last_four.extend(last_four.iter());
-            if unique_last_four.len() == 4 {
-                return Some(i + 1); // convert zero-based index to one-based solution
-            }
+    // "packet", "message", or a window width
+    fn window_from_name(name: &str) -> usize {
+        match name {
+            "packet" => Self::PACKET_MARKER_LEN,
+            "message" => Self::MESSAGE_MARKER_LEN,
+            _ => name
+                .parse()
+                .expect("Marker must be packet, message or a width"),
         }
+    }
 
-        None
+    fn open(&self) -> io::Result<File> {
+        File::open(&self.file_path)
     }
 
-    fn count_til_start_marker(&self) -> Option<usize> {
-        let chars = self.stream.chars();
-        let mut last_fourteen = VecDeque::new();
+    // how many bytes come before the end of the first run of `window`
+    // distinct ones
+    fn find_marker(&self, window: usize) -> io::Result<Option<usize>> {
+        marker::find_marker(self.open()?, window)
+    }
 
-        for (i, char) in chars.enumerate() {
-            last_fourteen.push_front(char);
-            if (last_fourteen.len() == 15) {
-                last_fourteen.pop_back();
-            }
+    // the first window of `window` bytes that's distinct enough for
+    // `tolerance`, along with the strongest competing windows
+    fn find_fuzzy_marker(
+        &self,
+        window: usize,
+        tolerance: Tolerance,
+        max_candidates: usize,
+    ) -> io::Result<FuzzyMarker> {
+        fuzzy::find_fuzzy_marker(self.open()?, window, tolerance, max_candidates)
+    }
 
-            let mut unique_last_fourteen: HashSet<char> = HashSet::new();
-            unique_last_fourteen.extend(last_fourteen.iter());
-            if unique_last_fourteen.len() == 14 {
-                return Some(i + 1); // convert zero-based index to one-based solution
-            }
-        }
+    // every marker of each width, and the longest distinct run, for each
+    // line of the file in turn
+    fn stats(&self, windows: &[usize]) -> io::Result<Vec<StreamStats>> {
+        runs::scan_lines(self.open()?, windows)
+    }
 
-        None
+    // the stream split into frames after its first marker of `window` bytes
+    fn frames(&self, window: usize, boundary: Boundary) -> io::Result<FrameDecoder<File>> {
+        Ok(FrameDecoder::new(self.open()?, window, boundary))
     }
 }
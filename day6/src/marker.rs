@@ -0,0 +1,90 @@
+use std::io::{self, BufReader, Read};
+
+// The last `window` bytes of a datastream, along with how often each byte
+// value occurs among them and how many of them repeat an earlier byte in
+// the window. Sliding forward by one byte only touches the byte coming in
+// and the byte dropping out, so each step costs the same however wide the
+// window is.
+pub struct MarkerWindow {
+    window: usize,
+    // ring buffer of the bytes in the window; byte `i` of the stream lives
+    // at `recent[i % window]` while it's in the window
+    recent: Vec<u8>,
+    counts: [usize; 256],
+    // how many bytes in the window have another copy earlier in it
+    duplicates: usize,
+    position: usize,
+}
+
+impl MarkerWindow {
+    pub fn new(window: usize) -> Self {
+        if window == 0 {
+            panic!("Marker window must be at least one byte wide");
+        }
+        Self {
+            window,
+            recent: vec![0; window],
+            counts: [0; 256],
+            duplicates: 0,
+            position: 0,
+        }
+    }
+
+    // slides the window forward over `byte`, returning whether the window is
+    // now full of distinct bytes
+    pub fn push(&mut self, byte: u8) -> bool {
+        let slot = self.position % self.window;
+        if self.position >= self.window {
+            let dropped = self.recent[slot] as usize;
+            self.counts[dropped] -= 1;
+            if self.counts[dropped] > 0 {
+                self.duplicates -= 1;
+            }
+        }
+
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] > 1 {
+            self.duplicates += 1;
+        }
+        self.recent[slot] = byte;
+        self.position += 1;
+
+        self.is_marker()
+    }
+
+    pub fn is_marker(&self) -> bool {
+        self.is_full() && self.duplicates == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.position >= self.window
+    }
+
+    pub fn duplicates(&self) -> usize {
+        self.duplicates
+    }
+
+    // how many different bytes are in the window
+    pub fn distinct(&self) -> usize {
+        self.position.min(self.window) - self.duplicates
+    }
+
+    // how many bytes have been pushed so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+// Returns how many bytes have to be read from `reader` before the most
+// recent `window` of them are all different, or `None` if that never
+// happens. Bytes are read through a buffer, so the stream never has to fit
+// in memory.
+pub fn find_marker<R: Read>(reader: R, window: usize) -> io::Result<Option<usize>> {
+    let mut marker_window = MarkerWindow::new(window);
+    for (i, byte) in BufReader::new(reader).bytes().enumerate() {
+        if marker_window.push(byte?) {
+            return Ok(Some(i + 1)); // convert zero-based index to one-based solution
+        }
+    }
+    Ok(None)
+}
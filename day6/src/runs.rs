@@ -0,0 +1,134 @@
+use std::fmt;
+use std::io::{self, BufReader, Read};
+use std::ops::Range;
+
+// Tracks the longest run of distinct bytes ending at the latest byte. When a
+// byte comes in, the run only has to restart just past that byte's previous
+// occurrence if it's inside the run, so each step is O(1). A window of any
+// width `w` is a marker exactly when this run is at least `w` long, which
+// is what lets one pass answer every window width at once.
+struct DistinctRun {
+    // one past the position where each byte value was last seen
+    last_seen: [usize; 256],
+    start: usize,
+    position: usize,
+}
+
+impl DistinctRun {
+    fn new() -> Self {
+        Self {
+            last_seen: [0; 256],
+            start: 0,
+            position: 0,
+        }
+    }
+
+    // returns the length of the run of distinct bytes ending at `byte`
+    fn push(&mut self, byte: u8) -> usize {
+        self.start = self.start.max(self.last_seen[byte as usize]);
+        self.position += 1;
+        self.last_seen[byte as usize] = self.position;
+        self.position - self.start
+    }
+}
+
+// What one pass over a datastream found
+pub struct StreamStats {
+    // one-based, as it appears in the input file
+    pub line_number: usize,
+    pub len: usize,
+    pub windows: Vec<usize>,
+    // `marker_ends[i]` lists every one-based position where a run of
+    // `windows[i]` distinct bytes ends, so its first entry is the answer to
+    // `find_marker(windows[i])`
+    pub marker_ends: Vec<Vec<usize>>,
+    // zero-based offsets of the longest run of distinct bytes, the earliest
+    // one if there's a tie
+    pub longest_run: Range<usize>,
+}
+
+impl StreamStats {
+    fn new(line_number: usize, windows: &[usize]) -> Self {
+        Self {
+            line_number,
+            len: 0,
+            windows: windows.to_vec(),
+            marker_ends: vec![Vec::new(); windows.len()],
+            longest_run: 0..0,
+        }
+    }
+}
+
+impl fmt::Display for StreamStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const SHOWN_POSITIONS: usize = 10;
+
+        writeln!(
+            f,
+            "Line {}: {} bytes, longest distinct run is {} bytes at {:?}",
+            self.line_number,
+            self.len,
+            self.longest_run.len(),
+            self.longest_run
+        )?;
+        for (window, ends) in self.windows.iter().zip(self.marker_ends.iter()) {
+            let shown = ends
+                .iter()
+                .take(SHOWN_POSITIONS)
+                .map(|end| end.to_string())
+                .collect::<Vec<_>>();
+            let more = if ends.len() > SHOWN_POSITIONS {
+                ", ..."
+            } else {
+                ""
+            };
+            writeln!(
+                f,
+                "  window {:>3}: {} markers, ending at [{}{}]",
+                window,
+                ends.len(),
+                shown.join(", "),
+                more
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Scans every line of `reader` as a separate datastream in a single pass,
+// recording where markers of each of the `windows` widths end and the
+// longest run of distinct bytes. Blank lines are skipped.
+pub fn scan_lines<R: Read>(reader: R, windows: &[usize]) -> io::Result<Vec<StreamStats>> {
+    let mut all_stats = Vec::new();
+    let mut stats = StreamStats::new(1, windows);
+    let mut run = DistinctRun::new();
+    for byte in BufReader::new(reader).bytes() {
+        match byte? {
+            b'\n' => {
+                let line_number = stats.line_number;
+                if stats.len > 0 {
+                    all_stats.push(stats);
+                }
+                stats = StreamStats::new(line_number + 1, windows);
+                run = DistinctRun::new();
+            }
+            b'\r' => (),
+            byte => {
+                let run_len = run.push(byte);
+                stats.len += 1;
+                for (&window, ends) in windows.iter().zip(stats.marker_ends.iter_mut()) {
+                    if run_len >= window {
+                        ends.push(stats.len);
+                    }
+                }
+                if run_len > stats.longest_run.len() {
+                    stats.longest_run = stats.len - run_len..stats.len;
+                }
+            }
+        }
+    }
+    if stats.len > 0 {
+        all_stats.push(stats);
+    }
+    Ok(all_stats)
+}
@@ -1,35 +1,114 @@
-use std::collections::HashMap;
+use std::env;
 use std::fs::File as StdFile;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
 
+use anyhow::Result;
 use regex::Regex;
 
-fn main() {
-    let analyzer = FilesystemAnalyzer::new("resources/input_1");
-    let directory_sizes = analyzer.directory_sizes();
-    let solution_1: u32 = directory_sizes
-        .values()
-        .filter(|&&size| size <= 100_000)
-        .sum();
-    println!("Part 1 solution: {}", solution_1);
-
-    let space_used = directory_sizes.get("").unwrap();
-    let space_remaining = 70_000_000 - space_used;
-    let space_needed = 30_000_000 - space_remaining;
-    let mut large_enough_sizes = directory_sizes
-        .values()
-        .filter(|&&size| size >= space_needed)
-        .collect::<Vec<_>>();
-    large_enough_sizes.sort();
-    let solution_2 = large_enough_sizes[0];
-    println!("Part 2 solution: {}", solution_2);
+mod planner;
+mod tree;
+mod walk;
+
+use planner::{DeletionPlanner, Objective};
+use tree::{Filesystem, TranscriptError};
+
+fn main() -> Result<()> {
+    let args = env::args().collect::<Vec<_>>();
+    match args.get(1).map(String::as_str) {
+        // e.g. `solve resources/input_1`
+        None | Some("solve") => {
+            let analyzer =
+                FilesystemAnalyzer::new(args.get(2).map_or("resources/input_1", String::as_str));
+            let filesystem = analyzer.filesystem()?;
+            for (line_number, command_line) in filesystem.unknown_commands.iter() {
+                eprintln!(
+                    "Line {}: skipped unknown command {:?}",
+                    line_number, command_line
+                );
+            }
+            let directory_sizes = filesystem.directory_sizes();
+            let solution_1: u64 = directory_sizes
+                .values()
+                .filter(|&&size| size <= 100_000)
+                .sum();
+            println!("Part 1 solution: {}", solution_1);
+
+            let space_used = filesystem.root().total_size;
+            let space_remaining = DeletionPlanner::DEFAULT_DISK_SIZE.saturating_sub(space_used);
+            let space_needed =
+                DeletionPlanner::DEFAULT_REQUIRED_FREE.saturating_sub(space_remaining);
+            let mut large_enough_sizes = directory_sizes
+                .values()
+                .filter(|&&size| size >= space_needed)
+                .collect::<Vec<_>>();
+            large_enough_sizes.sort();
+            let solution_2 = large_enough_sizes[0];
+            println!("Part 2 solution: {}", solution_2);
+        }
+        // e.g. `plan dirs resources/input_1 70000000 30000000 5`
+        Some("plan") => {
+            let objective = Objective::from_name(args.get(2).expect("Expected bytes or dirs"));
+            let analyzer =
+                FilesystemAnalyzer::new(args.get(3).map_or("resources/input_1", String::as_str));
+            let disk_size = match args.get(4) {
+                Some(disk_size) => disk_size.parse()?,
+                None => DeletionPlanner::DEFAULT_DISK_SIZE,
+            };
+            let required_free = match args.get(5) {
+                Some(required_free) => required_free.parse()?,
+                None => DeletionPlanner::DEFAULT_REQUIRED_FREE,
+            };
+            let max_plans = match args.get(6) {
+                Some(max_plans) => max_plans.parse()?,
+                None => DeletionPlanner::DEFAULT_MAX_PLANS,
+            };
+
+            let filesystem = analyzer.filesystem()?;
+            let planner = DeletionPlanner::new(
+                &filesystem,
+                objective,
+                max_plans,
+                DeletionPlanner::DEFAULT_NODE_LIMIT,
+            );
+            print!("{}", planner.plan(disk_size, required_free));
+        }
+        // e.g. `transcript ~/projects 42 > /tmp/projects`, where the optional
+        // seed shuffles the traversal
+        Some("transcript") => {
+            let root = args.get(2).expect("Expected a directory to walk");
+            let seed = args.get(3).map(|seed| seed.parse()).transpose()?;
+            walk::write_transcript(Path::new(root), seed, &mut io::stdout().lock())?;
+        }
+        // e.g. `sizes /tmp/projects`; prints every directory's size like
+        // `du -b`, though `du` also counts the directory entries themselves
+        Some("sizes") => {
+            let analyzer =
+                FilesystemAnalyzer::new(args.get(2).map_or("resources/input_1", String::as_str));
+            let mut directory_sizes = analyzer
+                .filesystem()?
+                .directory_sizes()
+                .into_iter()
+                .collect::<Vec<_>>();
+            directory_sizes.sort();
+            for (path, size) in directory_sizes {
+                println!("{}\t{}", size, path);
+            }
+        }
+        Some(other) => panic!("Unknown subcommand {}", other),
+    }
+
+    Ok(())
 }
 
 #[derive(Debug)]
 enum Command {
     ListObjects,
+    // any non-empty name, including `/` and `~`
     IntoDirectory { directory_name: String },
     BackDirectory,
+    // anything else after a `$ `, kept so it can be reported
+    Unknown { command_line: String },
 }
 
 #[derive(Clone, Debug)]
@@ -50,7 +129,7 @@ impl FilesystemObject {
 #[derive(Clone, Debug)]
 struct File {
     name: String,
-    size: u32,
+    size: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -70,9 +149,13 @@ impl Directory {
 enum Input {
     Command(Command),
     FilesystemObject(FilesystemObject),
+    // a line that's neither a command nor a listing entry, such as the
+    // output of an unknown command
+    Unrecognized(String),
 }
 
 struct FilesystemAnalyzer {
+    // one input per line of the transcript
     inputs: Vec<Input>,
 }
 
@@ -81,9 +164,10 @@ impl FilesystemAnalyzer {
         let file = StdFile::open(file_path).unwrap();
         let reader = BufReader::new(file);
 
-        let into_directory_regex = Regex::new(r"^\$ cd ([\w|/]+)$").unwrap();
-        let directory_regex = Regex::new(r"dir ([\w|/]+)$").unwrap();
-        let file_regex = Regex::new(r"^(\d+) ([\w|.]+)$").unwrap();
+        let into_directory_regex = Regex::new(r"^\$ cd (.+)$").unwrap();
+        let other_command_regex = Regex::new(r"^\$ (.*)$").unwrap();
+        let directory_regex = Regex::new(r"^dir (.+)$").unwrap();
+        let file_regex = Regex::new(r"^(\d+) (.+)$").unwrap();
 
         let mut inputs = Vec::new();
         for line in reader.lines() {
@@ -97,15 +181,22 @@ impl FilesystemAnalyzer {
                 Input::Command(Command::IntoDirectory {
                     directory_name: String::from(&captures[1]),
                 })
+            } else if let Some(captures) = other_command_regex.captures(line_content) {
+                Input::Command(Command::Unknown {
+                    command_line: String::from(&captures[1]),
+                })
             } else if let Some(captures) = directory_regex.captures(line_content) {
                 Input::FilesystemObject(FilesystemObject::Directory(Directory::new(&captures[1])))
-            } else if let Some(captures) = file_regex.captures(line_content) {
-                Input::FilesystemObject(FilesystemObject::File(File {
+            } else if let Some(file) = file_regex.captures(line_content).and_then(|captures| {
+                // a size too big for a u64 leaves the line unrecognized
+                Some(File {
                     name: String::from(&captures[2]),
-                    size: captures[1].parse::<u32>().unwrap(),
-                }))
+                    size: captures[1].parse::<u64>().ok()?,
+                })
+            }) {
+                Input::FilesystemObject(FilesystemObject::File(file))
             } else {
-                panic!("Unable to parse input line {}", line_content)
+                Input::Unrecognized(line_content.clone())
             };
 
             inputs.push(input);
@@ -114,45 +205,9 @@ impl FilesystemAnalyzer {
         Self { inputs }
     }
 
-    fn directory_sizes(&self) -> HashMap<String, u32> {
-        let mut current_path: Vec<&str> = Vec::new();
-        let mut directory_sizes: HashMap<String, u32> = HashMap::new();
-
-        // TODO just track current path and update size of EVERY relevant dir as you go
-        for input in self.inputs.iter() {
-            match input {
-                Input::FilesystemObject(FilesystemObject::File(file)) => {
-                    // increase running size of all directories in path; if they
-                    // don't exist yet, add key for them
-                    for n in 1..=current_path.len() {
-                        let path = current_path
-                            .iter()
-                            .take(n)
-                            .cloned()
-                            .collect::<Vec<_>>()
-                            .join("/");
-                        let directory_size = directory_sizes.entry(path).or_insert(0);
-                        *directory_size += file.size;
-                    }
-                }
-                Input::Command(Command::BackDirectory) => {
-                    current_path.pop();
-                }
-                Input::Command(Command::IntoDirectory { directory_name }) => {
-                    let parsed_name = if directory_name == "/" {
-                        // give root directory a special name that makes it less
-                        // cumbersome to join, and maybe split, on `/`
-                        ""
-                    } else {
-                        directory_name
-                    };
-                    current_path.push(parsed_name);
-                }
-                _ => (),
-            }
-        }
-
-        directory_sizes
+    // the directory tree the transcript describes, checked for consistency
+    fn filesystem(&self) -> Result<Filesystem, TranscriptError> {
+        Filesystem::from_inputs(&self.inputs)
     }
 }
 
@@ -0,0 +1,261 @@
+use std::fmt;
+
+use crate::tree::Filesystem;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Objective {
+    // delete as little data as possible, then as few directories
+    FewestBytes,
+    // delete as few directories as possible, then as little data
+    FewestDirectories,
+}
+
+impl Objective {
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "bytes" => Self::FewestBytes,
+            "dirs" => Self::FewestDirectories,
+            _ => panic!("Unknown objective {}", name),
+        }
+    }
+
+    // plans with smaller keys are better
+    fn key(&self, bytes: u64, num_directories: usize) -> (u64, u64) {
+        match self {
+            Self::FewestBytes => (bytes, num_directories as u64),
+            Self::FewestDirectories => (num_directories as u64, bytes),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DeletionPlan {
+    // arena indexes into the `Filesystem`; none is inside another
+    pub directories: Vec<usize>,
+    pub bytes: u64,
+}
+
+pub struct PlanReport<'a> {
+    filesystem: &'a Filesystem,
+    pub space_needed: u64,
+    // best first
+    pub plans: Vec<DeletionPlan>,
+    // false if the node limit cut the search short, so better plans may exist
+    pub complete: bool,
+}
+
+impl fmt::Display for PlanReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Space needed: {}", self.space_needed)?;
+        if !self.complete {
+            writeln!(
+                f,
+                "Search hit its node limit, so these may not be the best plans"
+            )?;
+        }
+        if self.plans.is_empty() {
+            writeln!(f, "No plan frees enough space")?;
+        }
+        for (rank, plan) in self.plans.iter().enumerate() {
+            let paths = plan
+                .directories
+                .iter()
+                .map(|&directory| self.filesystem.path(directory))
+                .collect::<Vec<_>>();
+            writeln!(
+                f,
+                "{}. {} bytes in {} directories: {}",
+                rank + 1,
+                plan.bytes,
+                plan.directories.len(),
+                paths.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Chooses directories to delete so that at least `required_free` bytes of a
+// `disk_size` disk end up free. Deleting a directory deletes everything in
+// it, so a plan never includes a directory along with one of its ancestors,
+// and the root itself is never deleted.
+//
+// Directories are decided one at a time in preorder, so that choosing one
+// can skip its whole subtree. Each is either deleted, or kept with its
+// subdirectories still to decide. A branch is abandoned once even deleting
+// every undecided directory couldn't free enough, or once it can't beat the
+// worst of the best plans found so far. A branch stops as soon as it frees
+// enough, since deleting more never helps either objective, and only counts
+// as a plan if every directory it deletes is needed. Otherwise leaving one
+// out would be a better plan, which a sibling branch finds on its own.
+pub struct DeletionPlanner<'a> {
+    filesystem: &'a Filesystem,
+    objective: Objective,
+    max_plans: usize,
+    node_limit: usize,
+    // directories in preorder
+    order: Vec<usize>,
+    // `subtree_ends[p]` is the position just past everything inside `order[p]`
+    subtree_ends: Vec<usize>,
+    // `freeable[p]` is the most that deleting directories from position `p`
+    // on could free: the total of each one whose parent comes before `p`
+    freeable: Vec<u64>,
+}
+
+struct SearchState {
+    chosen: Vec<usize>,
+    plans: Vec<DeletionPlan>,
+    nodes_visited: usize,
+}
+
+impl<'a> DeletionPlanner<'a> {
+    pub const DEFAULT_DISK_SIZE: u64 = 70_000_000;
+    pub const DEFAULT_REQUIRED_FREE: u64 = 30_000_000;
+    pub const DEFAULT_MAX_PLANS: usize = 5;
+    pub const DEFAULT_NODE_LIMIT: usize = 100_000_000;
+
+    pub fn new(
+        filesystem: &'a Filesystem,
+        objective: Objective,
+        max_plans: usize,
+        node_limit: usize,
+    ) -> Self {
+        let order = filesystem.preorder();
+        let mut positions = vec![0; order.len()];
+        for (position, &directory) in order.iter().enumerate() {
+            positions[directory] = position;
+        }
+
+        let mut subtree_ends = vec![0; order.len()];
+        for position in (0..order.len()).rev() {
+            let directory = filesystem.directory(order[position]);
+            subtree_ends[position] = directory
+                .subdirectories
+                .iter()
+                .map(|&subdirectory| subtree_ends[positions[subdirectory]])
+                .max()
+                .unwrap_or(position + 1);
+        }
+
+        // a directory at position `p` with its parent at `q` counts towards
+        // `freeable` for positions `q + 1` through `p`
+        let mut changes = vec![0i128; order.len() + 1];
+        for (position, &directory) in order.iter().enumerate() {
+            let node = filesystem.directory(directory);
+            if let Some(parent) = node.parent {
+                changes[positions[parent] + 1] += node.total_size as i128;
+                changes[position + 1] -= node.total_size as i128;
+            }
+        }
+        let mut freeable = Vec::with_capacity(order.len() + 1);
+        let mut running = 0;
+        for change in changes {
+            running += change;
+            freeable.push(running as u64);
+        }
+
+        Self {
+            filesystem,
+            objective,
+            max_plans,
+            node_limit,
+            order,
+            subtree_ends,
+            freeable,
+        }
+    }
+
+    pub fn plan(&self, disk_size: u64, required_free: u64) -> PlanReport<'a> {
+        let space_used = self.filesystem.root().total_size;
+        let space_free = disk_size.saturating_sub(space_used);
+        let space_needed = required_free.saturating_sub(space_free);
+
+        let mut state = SearchState {
+            chosen: Vec::new(),
+            plans: Vec::new(),
+            nodes_visited: 0,
+        };
+        // position 0 is the root, which is never deleted
+        self.search(&mut state, 1, 0, space_needed);
+
+        PlanReport {
+            filesystem: self.filesystem,
+            space_needed,
+            plans: state.plans,
+            complete: state.nodes_visited < self.node_limit,
+        }
+    }
+
+    fn search(&self, state: &mut SearchState, position: usize, freed: u64, space_needed: u64) {
+        if freed >= space_needed {
+            if self.is_minimal(state, freed, space_needed) {
+                self.record(state, freed);
+            }
+            return;
+        }
+        if position >= self.order.len()
+            || freed + self.freeable[position] < space_needed
+            || state.nodes_visited >= self.node_limit
+        {
+            return;
+        }
+        // whatever this branch ends with frees at least `space_needed` and
+        // deletes at least one more directory
+        if let Some(worst) = self.worst_kept(state) {
+            if self.objective.key(space_needed, state.chosen.len() + 1) >= worst {
+                return;
+            }
+        }
+        state.nodes_visited += 1;
+
+        let directory = self.order[position];
+        state.chosen.push(directory);
+        let total_size = self.filesystem.directory(directory).total_size;
+        self.search(
+            state,
+            self.subtree_ends[position],
+            freed + total_size,
+            space_needed,
+        );
+        state.chosen.pop();
+
+        self.search(state, position + 1, freed, space_needed);
+    }
+
+    // whether every chosen directory is needed to free `space_needed`
+    fn is_minimal(&self, state: &SearchState, freed: u64, space_needed: u64) -> bool {
+        state.chosen.iter().all(|&directory| {
+            freed - self.filesystem.directory(directory).total_size < space_needed
+        })
+    }
+
+    // the key a new plan has to beat to be kept, once the list is full
+    fn worst_kept(&self, state: &SearchState) -> Option<(u64, u64)> {
+        if state.plans.len() < self.max_plans {
+            return None;
+        }
+        state
+            .plans
+            .last()
+            .map(|plan| self.objective.key(plan.bytes, plan.directories.len()))
+    }
+
+    fn record(&self, state: &mut SearchState, bytes: u64) {
+        let key = self.objective.key(bytes, state.chosen.len());
+        let rank = state
+            .plans
+            .iter()
+            .position(|plan| key < self.objective.key(plan.bytes, plan.directories.len()))
+            .unwrap_or(state.plans.len());
+        if rank < self.max_plans {
+            state.plans.insert(
+                rank,
+                DeletionPlan {
+                    directories: state.chosen.clone(),
+                    bytes,
+                },
+            );
+            state.plans.truncate(self.max_plans);
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::{Command, File, FilesystemObject, Input};
+
+// Directories are stored in one arena and refer to each other by index. The
+// root is always at index 0, and every directory comes after its parent, so
+// walking the arena backwards visits children before their parents.
+pub struct Filesystem {
+    directories: Vec<DirectoryNode>,
+    // line numbers and text of commands that were skipped, along with any
+    // output they had
+    pub unknown_commands: Vec<(usize, String)>,
+}
+
+pub struct DirectoryNode {
+    pub name: String,
+    pub parent: Option<usize>,
+    // arena indexes of the directories directly inside this one
+    pub subdirectories: Vec<usize>,
+    pub files: Vec<File>,
+    // the line of the first `ls` of this directory, if it has been listed
+    pub listed_at: Option<usize>,
+    // everything inside this directory, however deeply nested
+    pub total_size: u64,
+}
+
+impl DirectoryNode {
+    fn new(name: &str, parent: Option<usize>) -> Self {
+        Self {
+            name: String::from(name),
+            parent,
+            subdirectories: Vec::new(),
+            files: Vec::new(),
+            listed_at: None,
+            total_size: 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TranscriptError {
+    CdAboveRoot {
+        line_number: usize,
+    },
+    // `cd` into a name the directory's listing didn't include
+    UnknownDirectory {
+        line_number: usize,
+        path: String,
+        name: String,
+    },
+    NotADirectory {
+        line_number: usize,
+        path: String,
+        name: String,
+    },
+    // a listing line that doesn't follow `$ ls`
+    OutputWithoutLs {
+        line_number: usize,
+    },
+    // a line that can't be parsed, outside an unknown command's output
+    UnrecognizedLine {
+        line_number: usize,
+        line: String,
+    },
+    // the same name listed twice, or as something it wasn't before
+    ConflictingEntry {
+        line_number: usize,
+        path: String,
+        name: String,
+    },
+    // a directory listed again with different contents
+    ListingMismatch {
+        line_number: usize,
+        path: String,
+        first_listed_at: usize,
+    },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::CdAboveRoot { line_number } => {
+                write!(f, "Line {}: can't `cd ..` out of the root", line_number)
+            }
+            Self::UnknownDirectory {
+                line_number,
+                path,
+                name,
+            } => write!(
+                f,
+                "Line {}: {} has no directory {:?} in its listing",
+                line_number, path, name
+            ),
+            Self::NotADirectory {
+                line_number,
+                path,
+                name,
+            } => write!(
+                f,
+                "Line {}: {:?} in {} is a file, not a directory",
+                line_number, name, path
+            ),
+            Self::OutputWithoutLs { line_number } => {
+                write!(f, "Line {}: listing output without an `ls`", line_number)
+            }
+            Self::UnrecognizedLine { line_number, line } => {
+                write!(f, "Line {}: unable to parse {:?}", line_number, line)
+            }
+            Self::ConflictingEntry {
+                line_number,
+                path,
+                name,
+            } => write!(
+                f,
+                "Line {}: {:?} in {} doesn't match what was seen there before",
+                line_number, name, path
+            ),
+            Self::ListingMismatch {
+                line_number,
+                path,
+                first_listed_at,
+            } => write!(
+                f,
+                "Line {}: listing of {} differs from its listing on line {}",
+                line_number, path, first_listed_at
+            ),
+        }
+    }
+}
+
+impl Error for TranscriptError {}
+
+// an `ls` whose output is still being read
+struct Listing<'a> {
+    directory: usize,
+    line_number: usize,
+    entries: Vec<(usize, &'a FilesystemObject)>,
+}
+
+impl Filesystem {
+    pub const ROOT: usize = 0;
+
+    // Replays a transcript, where `inputs[i]` came from line `i + 1`. A
+    // directory listed more than once must list the same contents each time,
+    // and is only counted once.
+    pub fn from_inputs(inputs: &[Input]) -> Result<Self, TranscriptError> {
+        let mut filesystem = Self {
+            directories: vec![DirectoryNode::new("/", None)],
+            unknown_commands: Vec::new(),
+        };
+        let mut current = Self::ROOT;
+        let mut listing: Option<Listing> = None;
+        // whether the lines being read are output of an unknown command
+        let mut skipping_output = false;
+
+        for (i, input) in inputs.iter().enumerate() {
+            let line_number = i + 1;
+            let command = match input {
+                _ if skipping_output && !matches!(input, Input::Command(_)) => continue,
+                Input::FilesystemObject(object) => match listing.as_mut() {
+                    Some(listing) => {
+                        listing.entries.push((line_number, object));
+                        continue;
+                    }
+                    None => return Err(TranscriptError::OutputWithoutLs { line_number }),
+                },
+                Input::Unrecognized(line) => {
+                    return Err(TranscriptError::UnrecognizedLine {
+                        line_number,
+                        line: line.clone(),
+                    })
+                }
+                Input::Command(command) => command,
+            };
+            skipping_output = false;
+
+            if let Some(finished) = listing.take() {
+                filesystem.add_listing(finished)?;
+            }
+            match command {
+                Command::ListObjects => {
+                    listing = Some(Listing {
+                        directory: current,
+                        line_number,
+                        entries: Vec::new(),
+                    })
+                }
+                Command::BackDirectory => {
+                    current = filesystem.directories[current]
+                        .parent
+                        .ok_or(TranscriptError::CdAboveRoot { line_number })?
+                }
+                Command::IntoDirectory { directory_name } if directory_name == "/" => {
+                    current = Self::ROOT
+                }
+                Command::IntoDirectory { directory_name } => {
+                    current = filesystem.enter(current, directory_name, line_number)?
+                }
+                Command::Unknown { command_line } => {
+                    filesystem
+                        .unknown_commands
+                        .push((line_number, command_line.clone()));
+                    skipping_output = true;
+                }
+            }
+        }
+        if let Some(finished) = listing {
+            filesystem.add_listing(finished)?;
+        }
+
+        filesystem.compute_sizes();
+        Ok(filesystem)
+    }
+
+    pub fn root(&self) -> &DirectoryNode {
+        &self.directories[Self::ROOT]
+    }
+
+    pub fn directory(&self, directory: usize) -> &DirectoryNode {
+        &self.directories[directory]
+    }
+
+    // every directory, each one followed immediately by everything inside it
+    pub fn preorder(&self) -> Vec<usize> {
+        let mut order = Vec::with_capacity(self.directories.len());
+        let mut to_visit = vec![Self::ROOT];
+        while let Some(directory) = to_visit.pop() {
+            order.push(directory);
+            to_visit.extend(self.directories[directory].subdirectories.iter().rev());
+        }
+        order
+    }
+
+    // the full path of a directory, like "/a/e", or "/" for the root
+    pub fn path(&self, directory: usize) -> String {
+        let mut names = Vec::new();
+        let mut next = Some(directory);
+        while let Some(index) = next {
+            if index != Self::ROOT {
+                names.push(self.directories[index].name.as_str());
+            }
+            next = self.directories[index].parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    // every directory's total size, keyed by its full path
+    pub fn directory_sizes(&self) -> HashMap<String, u64> {
+        (0..self.directories.len())
+            .map(|index| (self.path(index), self.directories[index].total_size))
+            .collect()
+    }
+
+    fn subdirectory(&self, directory: usize, name: &str) -> Option<usize> {
+        self.directories[directory]
+            .subdirectories
+            .iter()
+            .cloned()
+            .find(|&subdirectory| self.directories[subdirectory].name == name)
+    }
+
+    fn add_subdirectory(&mut self, directory: usize, name: &str) -> usize {
+        let subdirectory = self.directories.len();
+        self.directories
+            .push(DirectoryNode::new(name, Some(directory)));
+        self.directories[directory]
+            .subdirectories
+            .push(subdirectory);
+        subdirectory
+    }
+
+    // A directory can be entered before its parent has been listed; it's
+    // then checked against the listing once one arrives.
+    fn enter(
+        &mut self,
+        directory: usize,
+        name: &str,
+        line_number: usize,
+    ) -> Result<usize, TranscriptError> {
+        if let Some(subdirectory) = self.subdirectory(directory, name) {
+            return Ok(subdirectory);
+        }
+        let node = &self.directories[directory];
+        if node.files.iter().any(|file| file.name == name) {
+            return Err(TranscriptError::NotADirectory {
+                line_number,
+                path: self.path(directory),
+                name: String::from(name),
+            });
+        }
+        if node.listed_at.is_some() {
+            return Err(TranscriptError::UnknownDirectory {
+                line_number,
+                path: self.path(directory),
+                name: String::from(name),
+            });
+        }
+        Ok(self.add_subdirectory(directory, name))
+    }
+
+    fn add_listing(&mut self, listing: Listing) -> Result<(), TranscriptError> {
+        let directory = listing.directory;
+        let path = self.path(directory);
+        let first_listed_at = self.directories[directory].listed_at;
+        let mismatch = |first_listed_at| TranscriptError::ListingMismatch {
+            line_number: listing.line_number,
+            path: path.clone(),
+            first_listed_at,
+        };
+
+        let mut names = Vec::new();
+        for &(line_number, object) in listing.entries.iter() {
+            let name = object.name();
+            let conflict = TranscriptError::ConflictingEntry {
+                line_number,
+                path: path.clone(),
+                name: name.clone(),
+            };
+            if names.contains(&name) {
+                return Err(conflict);
+            }
+
+            let existing_size = self.directories[directory]
+                .files
+                .iter()
+                .find(|file| file.name == name)
+                .map(|file| file.size);
+            let existing_subdirectory = self.subdirectory(directory, &name);
+            match (object, existing_size, existing_subdirectory) {
+                (FilesystemObject::File(file), Some(size), None) if size == file.size => (),
+                (FilesystemObject::Directory(_), None, Some(_)) => (),
+                (_, None, None) if first_listed_at.is_some() => {
+                    return Err(mismatch(first_listed_at.unwrap()))
+                }
+                (FilesystemObject::File(file), None, None) => {
+                    self.directories[directory].files.push(file.clone())
+                }
+                (FilesystemObject::Directory(_), None, None) => {
+                    self.add_subdirectory(directory, &name);
+                }
+                _ => return Err(conflict),
+            }
+            names.push(name);
+        }
+
+        // anything seen here before, whether listed or entered with `cd`,
+        // has to show up again
+        let node = &self.directories[directory];
+        if node.files.len() + node.subdirectories.len() != names.len() {
+            return Err(match first_listed_at {
+                Some(first_listed_at) => mismatch(first_listed_at),
+                None => TranscriptError::UnknownDirectory {
+                    line_number: listing.line_number,
+                    path: path.clone(),
+                    name: node
+                        .subdirectories
+                        .iter()
+                        .map(|&subdirectory| self.directories[subdirectory].name.clone())
+                        .find(|name| !names.contains(name))
+                        .unwrap_or_default(),
+                },
+            });
+        }
+        self.directories[directory]
+            .listed_at
+            .get_or_insert(listing.line_number);
+        Ok(())
+    }
+
+    // each directory's size is its own files plus its subdirectories' totals;
+    // children come after their parents in the arena, so one backwards pass
+    // has every child finished before its parent needs it
+    fn compute_sizes(&mut self) {
+        for index in (0..self.directories.len()).rev() {
+            let node = &mut self.directories[index];
+            node.total_size += node.files.iter().map(|file| file.size).sum::<u64>();
+            let (total_size, parent) = (node.total_size, node.parent);
+            if let Some(parent) = parent {
+                self.directories[parent].total_size += total_size;
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+// Walks a real directory tree and writes a transcript of exploring it, in
+// the same `$ cd`/`$ ls` format as the puzzle input, so that
+// `FilesystemAnalyzer` can be checked against tools like `du -sb`. Every
+// directory is listed exactly once. Symlinks aren't followed, and like
+// other non-directories they're listed with their own size.
+//
+// With a seed, directories are visited in a shuffled order and each listing
+// is shuffled too. Moving between unrelated directories then goes through
+// `cd /`, which exercises the analyzer's handling of jumps back to the root.
+pub fn write_transcript<W: Write>(root: &Path, seed: Option<u64>, out: &mut W) -> io::Result<()> {
+    let mut listings = Vec::new();
+    collect_listings(root, &mut Vec::new(), &mut listings)?;
+
+    if let Some(seed) = seed {
+        let mut rng = XorShift::new(seed);
+        rng.shuffle(&mut listings);
+        for listing in listings.iter_mut() {
+            rng.shuffle(&mut listing.entries);
+        }
+    }
+
+    writeln!(out, "$ cd /")?;
+    let mut current: &[String] = &[];
+    for listing in listings.iter() {
+        let common = current
+            .iter()
+            .zip(listing.path.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let levels_up = current.len() - common;
+        if common == 0 && levels_up > 1 {
+            writeln!(out, "$ cd /")?;
+        } else {
+            for _ in 0..levels_up {
+                writeln!(out, "$ cd ..")?;
+            }
+        }
+        for name in listing.path[common..].iter() {
+            writeln!(out, "$ cd {}", name)?;
+        }
+        current = &listing.path;
+
+        writeln!(out, "$ ls")?;
+        for entry in listing.entries.iter() {
+            match entry {
+                Entry::Directory(name) => writeln!(out, "dir {}", name)?,
+                Entry::File(name, size) => writeln!(out, "{} {}", size, name)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+enum Entry {
+    Directory(String),
+    File(String, u64),
+}
+
+struct Listing {
+    // names from the root down to this directory; empty for the root
+    path: Vec<String>,
+    entries: Vec<Entry>,
+}
+
+// adds the listing of `directory` and everything under it, in preorder with
+// entries sorted by name
+fn collect_listings(
+    directory: &Path,
+    path: &mut Vec<String>,
+    listings: &mut Vec<Listing>,
+) -> io::Result<()> {
+    let mut entries = Vec::new();
+    let mut subdirectories = Vec::new();
+    for dir_entry in fs::read_dir(directory)? {
+        let dir_entry = dir_entry?;
+        let name = dir_entry.file_name().into_string().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{:?} isn't valid UTF-8", dir_entry.path()),
+            )
+        })?;
+        if name.contains('\n') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{:?} can't be written on one transcript line",
+                    dir_entry.path()
+                ),
+            ));
+        }
+        let metadata = fs::symlink_metadata(dir_entry.path())?;
+        if metadata.is_dir() {
+            subdirectories.push((name.clone(), dir_entry.path()));
+            entries.push(Entry::Directory(name));
+        } else {
+            entries.push(Entry::File(name, metadata.len()));
+        }
+    }
+    entries.sort_by(|a, b| entry_name(a).cmp(entry_name(b)));
+    subdirectories.sort();
+
+    listings.push(Listing {
+        path: path.clone(),
+        entries,
+    });
+    for (name, subdirectory) in subdirectories {
+        path.push(name);
+        collect_listings(&subdirectory, path, listings)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+fn entry_name(entry: &Entry) -> &str {
+    match entry {
+        Entry::Directory(name) | Entry::File(name, _) => name,
+    }
+}
+
+// a small seeded generator, so a shuffled transcript can be reproduced
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        // xorshift gets stuck on zero
+        Self(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // Fisher-Yates
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}